@@ -1,21 +1,72 @@
-use std::convert::TryInto;
+use std::collections::HashMap;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WinRule {
+    Rows,
+    Columns,
+    Diagonals, // both of them
+    FullHouse,
+}
+
+pub const DEFAULT_RULES: [WinRule; 2] = [WinRule::Rows, WinRule::Columns];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new(bits: usize) -> BitSet {
+        BitSet{words: vec![0; bits.div_ceil(64)]}
+    }
+
+    pub fn set(&mut self, bit: usize) {
+        self.words[bit / 64] |= 1 << (bit % 64);
+    }
+
+    pub fn get(&self, bit: usize) -> bool {
+        self.words[bit / 64] & (1 << (bit % 64)) != 0
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn contains_all(&self, other: &BitSet) -> bool {
+        self.words.iter().zip(other.words.iter()).all(|(a, b)| a & b == *b)
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Board {
-    pub values: [u8; Board::SIZE*Board::SIZE],
-    pub marked: u32,
+    pub size: usize, // rows & columns
+    pub values: Vec<u32>,
+    pub marked: BitSet,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Winner {
+    pub board: usize,
+    pub number: u32,
+    pub unmarked: u32,
+}
+
+impl Winner {
+    pub fn score(&self) -> u64 {
+        self.unmarked as u64 * self.number as u64
+    }
 }
 
 impl std::fmt::Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        for row in 0..Board::SIZE {
-            let line = &self.values[Board::SIZE*row..(Board::SIZE*row)+Board::SIZE];
-            for col in 0..Board::SIZE {
-                let last = if col == Board::SIZE-1 && row < Board::SIZE-1 { "\n" } else if col < Board::SIZE-1 { " " } else { "" };
+        let width = self.values.iter().max().map_or(1, |v| v.to_string().len()).max(2);
+        for (row, line) in self.values.chunks(self.size).enumerate() {
+            for (col, value) in line.iter().enumerate() {
+                let last = if col == self.size-1 && row < self.size-1 { "\n" } else if col < self.size-1 { " " } else { "" };
                 if self.is_marked(row, col) {
-                    write!(f, "\x1b[7m{x:>w$}\x1b[0m{sep}", w=2,x=line[col], sep=last)?
+                    write!(f, "\x1b[7m{x:>w$}\x1b[0m{sep}", w=width, x=value, sep=last)?
                 } else {
-                    write!(f, "{x:>w$}{sep}", w=2,x=line[col], sep=last)?
+                    write!(f, "{x:>w$}{sep}", w=width, x=value, sep=last)?
                 }
             }
         }
@@ -24,52 +75,127 @@ impl std::fmt::Display for Board {
 }
 
 impl Board {
-    pub const SIZE: usize = 5; // rows & columns
-    // TODO: compile-time check that marked has enough bits
-
-    pub fn new(values: &[u8]) -> Board {
-        return Board {
-            values: values.try_into().expect("Wrong number of numbers"),
-            marked: 0u32
+    pub fn new(values: &[u32]) -> Board {
+        let size = (values.len() as f64).sqrt() as usize;
+        assert_eq!(size * size, values.len(), "Board is not square");
+        Board {
+            size,
+            values: values.to_vec(),
+            marked: BitSet::new(values.len()),
         }
     }
 
     fn is_marked(&self, row: usize, col: usize) -> bool {
-        self.marked & (1 << (col + Board::SIZE*row)) != 0
+        self.marked.get(col + self.size*row)
     }
 
-    pub fn call(&mut self, number: u8) {
-        for n in 0..(Board::SIZE * Board::SIZE) {
+    pub fn call(&mut self, number: u32) {
+        for n in 0..self.values.len() {
             if self.values[n] == number {
-                self.marked |= 1 << n;
+                self.marked.set(n);
             }
         }
     }
 
-    pub fn is_winning(&self) -> bool {
-        if (self.marked.count_ones() as usize) < Board::SIZE {
+    pub fn is_winning(&self, lines: &[BitSet]) -> bool {
+        if self.marked.count_ones() < self.size {
             return false;
         }
-        for i in 0..Board::SIZE {
-            let row_mask = 0b11111 << (Board::SIZE*i);
-            if self.marked & row_mask == row_mask {
-                return true;
-            }
-            let col_mask = 0b00001_00001_00001_00001_00001 << i;
-            if self.marked & col_mask == col_mask {
-                return true;
-            }
-        }
-        return false;
+        lines.iter().any(|mask| self.marked.contains_all(mask))
     }
 
     pub fn score(&self) -> u32 {
-        let mut score = 0u32;
-        for n in 0..(Board::SIZE * Board::SIZE) {
-            if self.marked & (1 << n) == 0 {
-                score += self.values[n] as u32;
+        (0..self.values.len())
+            .filter(|&n| !self.marked.get(n))
+            .map(|n| self.values[n])
+            .sum()
+    }
+}
+
+/// Cells that make a winning line under any of the rules, for boards of the given size.
+pub fn win_lines(size: usize, rules: &[WinRule]) -> Vec<BitSet> {
+    rules.iter().flat_map(|&rule| line_masks(size, rule)).collect()
+}
+
+fn line_masks(size: usize, rule: WinRule) -> Vec<BitSet> {
+    let mask = |cells: &mut dyn Iterator<Item = usize>| {
+        let mut mask = BitSet::new(size * size);
+        cells.for_each(|n| mask.set(n));
+        mask
+    };
+    match rule {
+        WinRule::Rows => (0..size).map(|row| mask(&mut (0..size).map(|col| col + size*row))).collect(),
+        WinRule::Columns => (0..size).map(|col| mask(&mut (0..size).map(|row| col + size*row))).collect(),
+        WinRule::Diagonals => vec![
+            mask(&mut (0..size).map(|i| i + size*i)),
+            mask(&mut (0..size).map(|i| (size - 1 - i) + size*i)),
+        ],
+        WinRule::FullHouse => vec![mask(&mut (0..size*size))],
+    }
+}
+
+/// Calls numbers until all boards have won, returns winners in the order they won.
+pub fn play(numbers: &[u32], boards: &mut [Board], rules: &[WinRule]) -> Vec<Winner> {
+    let mut winners = vec![];
+    let mut lines = HashMap::new();
+    for &number in numbers {
+        for (idx, board) in boards.iter_mut().enumerate() {
+            if winners.iter().any(|w: &Winner| w.board == idx) {
+                continue;
             }
+            board.call(number);
+            let lines = lines.entry(board.size).or_insert_with(|| win_lines(board.size, rules));
+            if board.is_winning(lines) {
+                winners.push(Winner{board: idx, number, unmarked: board.score()});
+            }
+        }
+        if winners.len() == boards.len() {
+            break;
         }
-        return score;
     }
-}
\ No newline at end of file
+    winners
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 3x3 board numbered 1-9, row by row
+    fn board_after(calls: &[u32], rules: &[WinRule]) -> bool {
+        let mut board = Board::new(&(1..=9).collect::<Vec<_>>());
+        calls.iter().for_each(|&n| board.call(n));
+        board.is_winning(&win_lines(board.size, rules))
+    }
+
+    #[test]
+    fn default_rules() {
+        assert!(board_after(&[4, 5, 6], &DEFAULT_RULES));
+        assert!(board_after(&[2, 5, 8], &DEFAULT_RULES));
+        assert!(!board_after(&[1, 5, 9], &DEFAULT_RULES));
+    }
+
+    #[test]
+    fn diagonals() {
+        let rules = [WinRule::Rows, WinRule::Columns, WinRule::Diagonals];
+        assert!(board_after(&[1, 5, 9], &rules));
+        assert!(board_after(&[3, 5, 7], &rules));
+        assert!(!board_after(&[1, 5, 8], &rules));
+    }
+
+    #[test]
+    fn full_house() {
+        let rules = [WinRule::FullHouse];
+        assert!(!board_after(&[1, 2, 3, 4, 5, 6], &rules));
+        assert!(board_after(&(1..=9).collect::<Vec<_>>(), &rules));
+    }
+
+    #[test]
+    fn play_order() {
+        let mut boards = vec![Board::new(&[1, 2, 3, 4]), Board::new(&[4, 3, 2, 1, 5, 6, 7, 8, 9])];
+        let winners = play(&[7, 5, 2, 3], &mut boards, &[WinRule::Diagonals]);
+        assert_eq!(winners, vec![
+            Winner{board: 1, number: 2, unmarked: 4 + 3 + 1 + 6 + 8 + 9},
+            Winner{board: 0, number: 3, unmarked: 1 + 4},
+        ]);
+    }
+}
@@ -3,46 +3,74 @@ mod board;
 use std::io::{self, BufRead};
 use crate::board::*;
 
-fn read_input() -> Result<(Vec<u8>, Vec<Board>), std::io::Error> {
+fn read_input() -> Result<(Vec<u32>, Vec<Board>), std::io::Error> {
     let stdin = io::stdin();
     let mut boards: Vec<Board> = Vec::new();
-    
-    let mut buf = String::new();
-    let mut reader = stdin.lock();
+    let mut lines = stdin.lock().lines();
+
     // read numbers
-    reader.read_line(&mut buf)?;
-    let numbers = parse_numbers(&buf);
-
-    // read boards
-    while reader.read_line(&mut buf).ok() != Some(0) {
-        buf.clear();
-        for _ in 0..5 { reader.read_line(&mut buf)?; }
-        let board_numbers = parse_numbers(&buf);
-        boards.push(Board::new(board_numbers.as_slice()));
+    let numbers = parse_numbers(&lines.next().unwrap_or(Ok(String::new()))?);
+
+    // read boards, separated by empty lines
+    let mut board_numbers = vec![];
+    for line in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            if !board_numbers.is_empty() {
+                boards.push(Board::new(&board_numbers));
+                board_numbers.clear();
+            }
+            continue;
+        }
+        board_numbers.extend(parse_numbers(&line));
+    }
+    if !board_numbers.is_empty() {
+        boards.push(Board::new(&board_numbers));
     }
 
-    return Ok((numbers, boards));
+    Ok((numbers, boards))
 }
 
-fn parse_numbers(buf: &String) -> Vec<u8> {
-    return buf.split(|c: char| !c.is_numeric() )
+fn parse_numbers(buf: &str) -> Vec<u32> {
+    buf.split(|c: char| !c.is_numeric() )
         .filter(|s| !s.is_empty() )
-        .map(|s| u8::from_str_radix(s, 10).unwrap())
-        .collect();
+        .map(|s| s.parse().unwrap())
+        .collect()
+}
+
+// a full house is the only way to win with --full-house, so it doesn't mix with --diagonals
+fn read_rules() -> Result<Vec<WinRule>, String> {
+    let (mut diagonals, mut full_house) = (false, false);
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--diagonals" => diagonals = true,
+            "--full-house" => full_house = true,
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+    match (diagonals, full_house) {
+        (true, true) => Err("--full-house can't be combined with --diagonals".to_string()),
+        (_, true) => Ok(vec![WinRule::FullHouse]),
+        (true, _) => Ok([&DEFAULT_RULES[..], &[WinRule::Diagonals]].concat()),
+        _ => Ok(DEFAULT_RULES.to_vec()),
+    }
 }
 
 fn main() {
     let (numbers, mut boards) = read_input().unwrap();
+    let rules = read_rules().unwrap_or_else(|err| {
+        eprintln!("{}\nUsage: part1 [--diagonals | --full-house]", err);
+        std::process::exit(1);
+    });
 
-    for n in numbers {
-        for board in boards.iter_mut() {
-            let was_winning = board.is_winning();
-            board.call(n);
-            if board.is_winning() && !was_winning {
-                let score = board.score();
-                // first winner = part 1, last winner = part 2
-                println!("winner! {}*{} = {}", score, n, score*(n as u32));
-            }
-        }
+    let winners = play(&numbers, &mut boards, &rules);
+    for (i, winner) in winners.iter().enumerate() {
+        println!("winner #{}: board {}, {}*{} = {}", i+1, winner.board+1, winner.unmarked, winner.number, winner.score());
+        println!("{}\n", boards[winner.board]);
+    }
+    // first winner = part 1, last winner = part 2
+    if let (Some(first), Some(last)) = (winners.first(), winners.last()) {
+        println!("part 1: {}", first.score());
+        println!("part 2: {}", last.score());
     }
 }
@@ -0,0 +1,150 @@
+use matrix::Matrix;
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Model {
+    pub reset: u8, // timer after giving birth
+    pub newborn: u8, // timer of a new fish
+}
+
+pub const DEFAULT_MODEL: Model = Model{reset: 6, newborn: 8};
+
+impl Model {
+    pub fn timers(&self) -> usize {
+        self.reset.max(self.newborn) as usize + 1
+    }
+
+    /// Number of fish with each timer value.
+    pub fn census(&self, fish: &[u8]) -> Result<Vec<BigUint>, String> {
+        let mut counts = vec![BigUint::zero(); self.timers()];
+        for &f in fish {
+            *counts.get_mut(f as usize).ok_or(format!("Timer {} is over {}", f, self.timers()-1))? += 1u32;
+        }
+        Ok(counts)
+    }
+
+    /// Fish sharing a timer value behave alike, so a day maps the census linearly:
+    /// entry (to, from) is how many fish with timer `to` one fish with timer `from` becomes.
+    fn transition(&self) -> Matrix<BigUint> {
+        let mut m = Matrix::zero(self.timers());
+        for timer in 1..self.timers() {
+            *m.get_mut(timer-1, timer) += 1u32;
+        }
+        *m.get_mut(self.reset as usize, 0) += 1u32;
+        *m.get_mut(self.newborn as usize, 0) += 1u32;
+        m
+    }
+
+    /// Fish counts per timer value after `days`.
+    pub fn jump(&self, census: &[BigUint], days: u64) -> Vec<BigUint> {
+        // BigUint arithmetic can't overflow
+        self.transition().checked_pow(days).and_then(|m| m.checked_apply(census)).unwrap()
+    }
+
+    pub fn population(&self, fish: &[u8], days: u64) -> Result<BigUint, String> {
+        Ok(self.jump(&self.census(fish)?, days).into_iter().sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lanternfish::{Model, DEFAULT_MODEL};
+    use num_bigint::BigUint;
+
+    const EXAMPLE: [u8; 5] = [3, 4, 3, 1, 2];
+
+    // the fish one by one, for checking
+    fn simulate(model: &Model, fish: &[u8], days: u64) -> usize {
+        let mut fish = fish.to_vec();
+        for _ in 0..days {
+            let mut births = 0;
+            for f in fish.iter_mut() {
+                if *f == 0 {
+                    *f = model.reset;
+                    births += 1;
+                } else {
+                    *f -= 1;
+                }
+            }
+            fish.extend(std::iter::repeat_n(model.newborn, births));
+        }
+        fish.len()
+    }
+
+    #[test]
+    fn example() {
+        assert_eq!(
+            DEFAULT_MODEL.population(&EXAMPLE, 18),
+            Ok(BigUint::from(26u32))
+        );
+        assert_eq!(
+            DEFAULT_MODEL.population(&EXAMPLE, 80),
+            Ok(BigUint::from(5934u32))
+        );
+        assert_eq!(
+            DEFAULT_MODEL.population(&EXAMPLE, 256),
+            Ok(BigUint::from(26984457539u64))
+        );
+    }
+
+    #[test]
+    fn other_models() {
+        // every fish gives birth every day: the school doubles
+        let doubling = Model {
+            reset: 0,
+            newborn: 0,
+        };
+        assert_eq!(doubling.population(&[0], 10), Ok(BigUint::from(1024u32)));
+        assert_eq!(
+            doubling.population(&[0, 0, 0], 100),
+            Ok(BigUint::from(3u32) << 100)
+        );
+        // newborns wait a day, parents don't: Fibonacci
+        let fibonacci = Model {
+            reset: 0,
+            newborn: 1,
+        };
+        let expected = [1u32, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144];
+        for (days, &count) in expected.iter().enumerate() {
+            assert_eq!(
+                fibonacci.population(&[0], days as u64),
+                Ok(BigUint::from(count))
+            );
+        }
+        // newborns start on the parent's timer: doubles every other day
+        let pairs = Model {
+            reset: 1,
+            newborn: 1,
+        };
+        assert_eq!(pairs.population(&[0], 9), Ok(BigUint::from(32u32)));
+        assert_eq!(pairs.population(&[1], 9), Ok(BigUint::from(16u32)));
+    }
+
+    #[test]
+    fn matches_simulation() {
+        for (reset, newborn) in [(6, 8), (8, 6), (5, 9), (7, 7), (6, 12), (10, 3), (4, 8)] {
+            let model = Model { reset, newborn };
+            for days in 0..=80 {
+                assert_eq!(
+                    model.population(&EXAMPLE, days),
+                    Ok(BigUint::from(simulate(&model, &EXAMPLE, days))),
+                    "reset {} newborn {} day {}",
+                    reset,
+                    newborn,
+                    days
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bad_timer() {
+        assert!(DEFAULT_MODEL.population(&[9], 1).is_err());
+        let fibonacci = Model {
+            reset: 0,
+            newborn: 1,
+        };
+        assert!(fibonacci.population(&[2], 1).is_err());
+    }
+}
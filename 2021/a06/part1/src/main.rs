@@ -1,53 +1,42 @@
+mod lanternfish;
+
 use std::io::{self, BufRead};
+use crate::lanternfish::*;
 
-fn read_input() -> Result<Vec<u8>, std::io::Error> {
+fn read_input() -> Result<Vec<u8>, String> {
     let mut buf = String::new();
-    let stdin = io::stdin();
-    let mut reader = stdin.lock();
-    // read numbers
-    reader.read_line(&mut buf)?;
-
-    return Ok(buf.split(|c: char| !c.is_numeric() )
-    .filter(|s| !s.is_empty() )
-    .map(|s| u8::from_str_radix(s, 10).unwrap())
-    .collect());
+    io::stdin().lock().read_line(&mut buf).map_err(|err| err.to_string())?;
+    buf.trim().split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().map_err(|_| format!("Invalid timer: {}", s)))
+        .collect()
 }
 
-fn run_day(fish: &mut Vec<u8>) -> u32 {
-    let mut births = 0;
-    for f in fish {
-        if *f == 0 {
-            // new fish
-            *f = 6;
-            births += 1;
-        } else {
-            *f -= 1;
-        }
-    }
-    return births;
+fn fail(err: &str) -> ! {
+    eprintln!("{}", err);
+    std::process::exit(1);
 }
 
-fn print_fish(day: i32, fish: &Vec<u8>) {
-    if day == 0 {
-        print!("Initial state: ");
-    } else {
-        print!("After {:>2} days: ", day);
+fn main() {
+    // [--reset N] [--newborn N] [days...]
+    let mut model = DEFAULT_MODEL;
+    let mut days = vec![];
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().and_then(|v| v.parse().ok()).unwrap_or_else(|| fail(&format!("{} needs a timer value", arg)));
+        match arg.as_str() {
+            "--reset" => model.reset = value(),
+            "--newborn" => model.newborn = value(),
+            _ => days.push(arg.parse::<u64>().unwrap_or_else(|_| fail(&format!("Invalid number of days: {}", arg)))),
+        }
     }
-    for f in fish {
-        print!("{},", f);
+    if days.is_empty() {
+        days = vec![80, 256];
     }
-    print!("\n");
-}
 
-fn main() {
-    let mut fish = read_input().ok().unwrap();
-    print_fish(0, &fish);
-    for day in 1..=80 {
-        let births = run_day(&mut fish);
-        for b in 0..births {
-            fish.push(8);
-        }
-        //print_fish(day, &fish);
+    let fish = read_input().unwrap_or_else(|err| fail(&err));
+    for &days in days.iter() {
+        let population = model.population(&fish, days).unwrap_or_else(|err| fail(&err));
+        println!("{} fish after {} days", population, days);
     }
-    println!("total: {}", fish.len());
 }
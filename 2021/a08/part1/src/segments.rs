@@ -0,0 +1,248 @@
+/*
+Digits are sets of lit segments, stored as bitmasks with bit 0 for segment a.
+Observed patterns are sets of wires, which go to the segments in some unknown
+order. Each observed pattern is matched with a digit of the same size, and every
+match narrows down the segments each wire can go to, until only permutations
+consistent with all the patterns are left.
+*/
+
+pub type Pattern = u8;
+
+pub const MAX_SEGMENTS: usize = Pattern::BITS as usize;
+
+pub const STANDARD_DIGITS: [&str; 10] = ["abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg"];
+
+fn segment_name(segment: usize) -> char {
+    (b'a' + segment as u8) as char
+}
+
+pub fn parse_pattern(word: &str, segments: usize) -> Result<Pattern, String> {
+    let mut pattern = 0;
+    for c in word.chars() {
+        let segment = (c as usize).wrapping_sub('a' as usize);
+        if segment >= segments {
+            return Err(format!("Unknown segment {} in {}", c, word));
+        }
+        pattern |= 1 << segment;
+    }
+    Ok(pattern)
+}
+
+/// The segments lit for each digit, in order.
+#[derive(Debug, Clone)]
+pub struct DigitTable {
+    pub segments: usize,
+    pub digits: Vec<Pattern>,
+}
+
+impl DigitTable {
+    pub fn standard() -> DigitTable {
+        DigitTable::parse(&STANDARD_DIGITS).unwrap()
+    }
+
+    pub fn parse(words: &[&str]) -> Result<DigitTable, String> {
+        let digits = words.iter().map(|w| parse_pattern(w, MAX_SEGMENTS)).collect::<Result<Vec<_>,_>>()?;
+        let used = digits.iter().fold(0, |acc, d| acc | d);
+        let segments = (Pattern::BITS - used.leading_zeros()) as usize;
+        if used.count_ones() as usize != segments {
+            return Err(format!("Segment {} is never used", segment_name(used.trailing_ones() as usize)));
+        }
+        for (i, d) in digits.iter().enumerate() {
+            if digits[..i].contains(d) {
+                return Err(format!("Digits {} and {} look the same", digits[..i].iter().position(|x| x == d).unwrap(), i));
+            }
+        }
+        Ok(DigitTable{segments, digits})
+    }
+
+    pub fn digit(&self, segments: Pattern) -> Option<usize> {
+        self.digits.iter().position(|&d| d == segments)
+    }
+
+    /// Digits that can be told apart by their number of segments alone.
+    pub fn unique_sizes(&self) -> Vec<usize> {
+        (0..self.digits.len())
+            .filter(|&i| self.digits.iter().filter(|d| d.count_ones() == self.digits[i].count_ones()).count() == 1)
+            .collect()
+    }
+}
+
+/// Which segment each wire goes to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wiring {
+    pub segment_of: Vec<usize>,
+}
+
+impl Wiring {
+    pub fn translate(&self, wires: Pattern) -> Pattern {
+        self.segment_of.iter().enumerate()
+            .filter(|(wire, _)| wires & (1 << wire) != 0)
+            .fold(0, |acc, (_, segment)| acc | (1 << segment))
+    }
+}
+
+impl std::fmt::Display for Wiring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        for (wire, &segment) in self.segment_of.iter().enumerate() {
+            write!(f, "{}{}", if wire > 0 { " " } else { "" }, segment_name(wire))?;
+            write!(f, "->{}", segment_name(segment))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// No wiring makes every pattern a digit.
+    Contradiction,
+    /// More than one wiring fits, up to a few of them.
+    Ambiguous(Vec<Wiring>),
+}
+
+impl std::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        match self {
+            SolveError::Contradiction => write!(f, "no wiring fits"),
+            SolveError::Ambiguous(wirings) => {
+                write!(f, "{} wirings fit", if wirings.len() < AMBIGUITY_LIMIT { wirings.len().to_string() } else { "many".to_string() })?;
+                for wiring in wirings {
+                    write!(f, "\n  {}", wiring)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+const AMBIGUITY_LIMIT: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub patterns: Vec<Pattern>,
+    pub output: Vec<Pattern>,
+}
+
+impl Entry {
+    pub fn parse(line: &str, segments: usize) -> Result<Entry, String> {
+        // "acedgfb cdfbe gcdfa ... | cdfeb fcadb cdfeb cdbaf"
+        let (patterns, output) = line.split_once('|').ok_or(format!("Missing | in {}", line))?;
+        let words = |s: &str| s.split_whitespace().map(|w| parse_pattern(w, segments)).collect::<Result<Vec<_>,_>>();
+        Ok(Entry{patterns: words(patterns)?, output: words(output)?})
+    }
+
+    /// Finds the only wiring that turns every pattern into a digit.
+    pub fn solve(&self, table: &DigitTable) -> Result<Wiring, SolveError> {
+        let mut observed = self.patterns.iter().chain(self.output.iter()).copied().collect::<Vec<_>>();
+        observed.sort_unstable();
+        observed.dedup();
+        // most constrained first
+        observed.sort_by_key(|p| table.digits.iter().filter(|d| d.count_ones() == p.count_ones()).count());
+
+        let all = if table.segments == MAX_SEGMENTS { Pattern::MAX } else { (1 << table.segments) - 1 };
+        let mut solutions = vec![];
+        search(table, &observed, &mut vec![false; table.digits.len()], vec![all; table.segments], &mut solutions);
+        match solutions.len() {
+            0 => Err(SolveError::Contradiction),
+            1 => Ok(solutions.pop().unwrap()),
+            _ => Err(SolveError::Ambiguous(solutions)),
+        }
+    }
+
+    /// The output digits under `wiring`, `None` for patterns that aren't digits.
+    pub fn decode(&self, table: &DigitTable, wiring: &Wiring) -> Vec<Option<usize>> {
+        self.output.iter().map(|&p| table.digit(wiring.translate(p))).collect()
+    }
+}
+
+// candidates[wire] are the segments that wire can still go to
+fn search(table: &DigitTable, observed: &[Pattern], used: &mut Vec<bool>, candidates: Vec<Pattern>, solutions: &mut Vec<Wiring>) {
+    if solutions.len() >= AMBIGUITY_LIMIT {
+        return;
+    }
+    let (&pattern, rest) = match observed.split_first() {
+        Some(split) => split,
+        None => return permutations(&candidates, &mut vec![], 0, solutions),
+    };
+    for digit in 0..table.digits.len() {
+        let segments = table.digits[digit];
+        if used[digit] || segments.count_ones() != pattern.count_ones() {
+            continue;
+        }
+        // wires in the pattern go to the digit's segments, the others don't
+        let narrowed = candidates.iter().enumerate()
+            .map(|(wire, &c)| if pattern & (1 << wire) != 0 { c & segments } else { c & !segments })
+            .collect::<Vec<_>>();
+        if narrowed.contains(&0) {
+            continue;
+        }
+        used[digit] = true;
+        search(table, rest, used, narrowed, solutions);
+        used[digit] = false;
+    }
+}
+
+fn permutations(candidates: &[Pattern], segment_of: &mut Vec<usize>, taken: Pattern, solutions: &mut Vec<Wiring>) {
+    if solutions.len() >= AMBIGUITY_LIMIT {
+        return;
+    }
+    let wire = segment_of.len();
+    if wire == candidates.len() {
+        solutions.push(Wiring{segment_of: segment_of.clone()});
+        return;
+    }
+    for segment in 0..candidates.len() {
+        if candidates[wire] & !taken & (1 << segment) != 0 {
+            segment_of.push(segment);
+            permutations(candidates, segment_of, taken | (1 << segment), solutions);
+            segment_of.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 10] = [
+        "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe",
+        "edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc",
+        "fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg",
+        "fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb",
+        "aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea",
+        "fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb",
+        "dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe",
+        "bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef",
+        "egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb",
+        "gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce",
+    ];
+
+    fn value(table: &DigitTable, line: &str) -> u64 {
+        let entry = Entry::parse(line, table.segments).unwrap();
+        let wiring = entry.solve(table).unwrap();
+        entry.decode(table, &wiring).iter().fold(0, |acc, d| acc * 10 + d.unwrap() as u64)
+    }
+
+    #[test]
+    fn example() {
+        let table = DigitTable::standard();
+        assert_eq!(table.unique_sizes(), vec![1, 4, 7, 8]);
+        let line = "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+        let entry = Entry::parse(line, table.segments).unwrap();
+        assert_eq!(entry.solve(&table).unwrap().to_string(), "a->c b->f c->g d->a e->b f->d g->e");
+        assert_eq!(value(&table, line), 5353);
+        let values = EXAMPLE.iter().map(|line| value(&table, line)).collect::<Vec<_>>();
+        assert_eq!(values, vec![8394, 9781, 1197, 9361, 4873, 8418, 4548, 1625, 8717, 4315]);
+        assert_eq!(values.iter().sum::<u64>(), 61229);
+    }
+
+    #[test]
+    fn unsolvable() {
+        let table = DigitTable::standard();
+        // two patterns with one wire can't both be digits
+        let entry = Entry::parse("a b | a", table.segments).unwrap();
+        assert_eq!(entry.solve(&table), Err(SolveError::Contradiction));
+        // a lone 1 could be wired either way round
+        let entry = Entry::parse("ab | ab", table.segments).unwrap();
+        assert!(matches!(entry.solve(&table), Err(SolveError::Ambiguous(_))));
+    }
+}
@@ -1,97 +1,33 @@
-use std::io::{self, BufRead};
-
-fn is_opener(c: &char) -> bool {
-    match c {
-        '(' => true,
-        '[' => true,
-        '{' => true,
-        '<' => true,
-        _ => false
-    }
-}
-
-fn is_closer(c: &char) -> bool {
-    match c {
-        ')' => true,
-        ']' => true,
-        '}' => true,
-        '>' => true,
-        _ => false
-    }
-}
-
-fn matching_closer(c: &char) -> char {
-    match c {
-        '(' => ')',
-        '[' => ']',
-        '{' => '}',
-        '<' => '>',
-        _ => panic!("not an opener!")
-    }
-}
-
-fn bad_closer_score(c: &char) -> u32 {
-    match c {
-        ')' => 3,
-        ']' => 57,
-        '}' => 1197,
-        '>' => 25137,
-        _ => 0
-    }
-}
+mod syntax;
 
-fn completion_score(c: &char) -> u32 {
-    match c {
-        ')' => 1,
-        ']' => 2,
-        '}' => 3,
-        '>' => 4,
-        _ => 0
-    }
-}
-
-fn read_line(buf: &String) -> Vec<char> {
-    buf
-        .chars()
-        .filter(|c| is_opener(c) || is_closer(c) )
-        .collect::<Vec<_>>()
-}
-
-fn read_input() -> Vec<Vec<char>> {
-    io::stdin().lock().lines()
-        .map(|line| read_line(&line.ok().unwrap()) )
-        .collect()
-}
+use std::io::{self, BufRead};
+use crate::syntax::*;
 
 fn main() {
-    let input = read_input();
-
-    let mut part1_score = 0u32;
-    let mut completion_scores = vec![];
-    'line: for line in input {
-        let mut closers = vec![];
-        for c in line {
-            if is_opener(&c) {
-                closers.push(matching_closer(&c));
-            } else if c == closers.pop().unwrap() {
-                // valid closer
-
-            } else {
-                // invalid closer
-                part1_score += bad_closer_score(&c);
-                continue 'line;
-            }
+    // --pairs "()=3 []=57" for other brackets, scored in the order given
+    let args = std::env::args().collect::<Vec<_>>();
+    let language = match args.iter().position(|arg| arg == "--pairs") {
+        Some(idx) => Language::parse(args.get(idx+1).map_or("", String::as_str)).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }),
+        None => Language::standard(),
+    };
+
+    let mut scores = Scoreboard::default();
+    let lines = io::stdin().lock().lines().map_while(Result::ok);
+    for (number, line, result) in language.check_lines(lines) {
+        if result != LineResult::Valid {
+            println!("line {}: {} - {}", number, line, result);
         }
-        // completion score
-        let mut line_comp_score = 0u64;
-        for c in closers.into_iter().rev() {
-            line_comp_score = 5 * line_comp_score + completion_score(&c) as u64;
+        if let Err(err) = scores.add(&language, &result) {
+            println!("line {}: {}", number, err);
         }
-        completion_scores.push(line_comp_score);
     }
-    println!("part 1 score {}", part1_score);
-
-    completion_scores.sort();
-    println!("part 2 score {}", completion_scores[completion_scores.len() / 2]);
-
+    println!("part 1 score {}", scores.error_total);
+    match scores.middle_completion_score() {
+        Some(score) => println!("part 2 score {}", score),
+        None if scores.completion_overflows > 0 => println!("part 2: {} completion scores overflow", scores.completion_overflows),
+        None => println!("part 2: no incomplete lines"),
+    }
 }
@@ -0,0 +1,232 @@
+#[derive(Debug, Clone, Copy)]
+pub struct BracketPair {
+    pub open: char,
+    pub close: char,
+    pub error_score: u64, // for a corrupted line closed with this
+    pub completion_score: u64, // for each of this closer added to complete a line
+}
+
+#[derive(Debug, Clone)]
+pub struct Language {
+    pub pairs: Vec<BracketPair>,
+    pub completion_base: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineResult {
+    Valid,
+    /// Unexpected closer at `column` (1-based), `expected` is None if nothing was open.
+    Corrupted { column: usize, expected: Option<char>, found: char },
+    Incomplete { completion: String },
+}
+
+impl std::fmt::Display for LineResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        match self {
+            LineResult::Valid => write!(f, "valid"),
+            LineResult::Corrupted{column, expected: Some(expected), found} => write!(f, "corrupted at column {}: expected {}, but found {} instead", column, expected, found),
+            LineResult::Corrupted{column, expected: None, found} => write!(f, "corrupted at column {}: nothing to close with {}", column, found),
+            LineResult::Incomplete{completion} => write!(f, "incomplete, complete by adding {}", completion),
+        }
+    }
+}
+
+impl Language {
+    pub fn standard() -> Language {
+        Language {
+            pairs: vec![
+                BracketPair{open: '(', close: ')', error_score: 3, completion_score: 1},
+                BracketPair{open: '[', close: ']', error_score: 57, completion_score: 2},
+                BracketPair{open: '{', close: '}', error_score: 1197, completion_score: 3},
+                BracketPair{open: '<', close: '>', error_score: 25137, completion_score: 4},
+            ],
+            completion_base: 5,
+        }
+    }
+
+    /// Pairs written as `()=3 []=57`, with completion scores 1, 2, ... in order.
+    pub fn parse(spec: &str) -> Result<Language, String> {
+        let pairs = spec.split_whitespace().enumerate().map(|(i, word)| {
+            let (brackets, score) = word.split_once('=').ok_or(format!("Missing error score in {}", word))?;
+            let brackets = brackets.chars().collect::<Vec<_>>();
+            if brackets.len() != 2 || brackets[0] == brackets[1] {
+                return Err(format!("Invalid bracket pair {}", word));
+            }
+            let error_score = score.parse().map_err(|_| format!("Invalid error score in {}", word))?;
+            Ok(BracketPair{open: brackets[0], close: brackets[1], error_score, completion_score: i as u64 + 1})
+        }).collect::<Result<Vec<_>,_>>()?;
+        if pairs.is_empty() {
+            return Err("No bracket pairs".to_string());
+        }
+        for (i, pair) in pairs.iter().enumerate() {
+            if pairs[..i].iter().any(|p| [p.open, p.close].iter().any(|c| *c == pair.open || *c == pair.close)) {
+                return Err(format!("Bracket {}{} is used twice", pair.open, pair.close));
+            }
+        }
+        Ok(Language{completion_base: pairs.len() as u64 + 1, pairs})
+    }
+
+    fn opened_by(&self, c: char) -> Option<&BracketPair> {
+        self.pairs.iter().find(|p| p.open == c)
+    }
+
+    fn closed_by(&self, c: char) -> Option<&BracketPair> {
+        self.pairs.iter().find(|p| p.close == c)
+    }
+
+    pub fn check(&self, line: &str) -> LineResult {
+        let mut checker = Checker::new(self);
+        for c in line.chars() {
+            if let Err(result) = checker.push(c) {
+                return result;
+            }
+        }
+        checker.finish()
+    }
+
+    /// Checks lines as they come, skipping blank ones. Line numbers start at 1 and count blank lines.
+    pub fn check_lines<'a, I: Iterator<Item = String> + 'a>(&'a self, lines: I) -> impl Iterator<Item = (usize, String, LineResult)> + 'a {
+        lines.enumerate().filter(|(_, line)| !line.is_empty()).map(move |(idx, line)| {
+            let result = self.check(&line);
+            (idx+1, line, result)
+        })
+    }
+
+    pub fn error_score(&self, result: &LineResult) -> u64 {
+        match result {
+            LineResult::Corrupted{found, ..} => self.closed_by(*found).map_or(0, |p| p.error_score),
+            _ => 0,
+        }
+    }
+
+    /// `None` if the line isn't incomplete, errors if the score doesn't fit.
+    pub fn completion_score(&self, result: &LineResult) -> Result<Option<u64>, String> {
+        match result {
+            LineResult::Incomplete{completion} => completion.chars().try_fold(0u64, |score, c| {
+                score.checked_mul(self.completion_base)
+                    .and_then(|score| score.checked_add(self.closed_by(c).map_or(0, |p| p.completion_score)))
+                    .ok_or(format!("completion score for {} overflows", completion))
+            }).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Checks a line one character at a time. Characters that aren't brackets are ignored.
+pub struct Checker<'a> {
+    language: &'a Language,
+    closers: Vec<char>,
+    column: usize,
+}
+
+impl<'a> Checker<'a> {
+    pub fn new(language: &'a Language) -> Checker<'a> {
+        Checker{language, closers: vec![], column: 0}
+    }
+
+    /// Errors with the result once the line is corrupted.
+    pub fn push(&mut self, c: char) -> Result<(), LineResult> {
+        self.column += 1;
+        if let Some(pair) = self.language.opened_by(c) {
+            self.closers.push(pair.close);
+        } else if self.language.closed_by(c).is_some() {
+            let expected = self.closers.pop();
+            if expected != Some(c) {
+                return Err(LineResult::Corrupted{column: self.column, expected, found: c});
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> LineResult {
+        if self.closers.is_empty() {
+            LineResult::Valid
+        } else {
+            LineResult::Incomplete{completion: self.closers.into_iter().rev().collect()}
+        }
+    }
+}
+
+/// Running totals over checked lines.
+#[derive(Debug, Default)]
+pub struct Scoreboard {
+    pub error_total: u64,
+    pub completion_scores: Vec<u64>,
+    pub completion_overflows: usize,
+}
+
+impl Scoreboard {
+    /// Errors if the completion score overflows, which leaves the middle score unknown.
+    pub fn add(&mut self, language: &Language, result: &LineResult) -> Result<(), String> {
+        self.error_total += language.error_score(result);
+        match language.completion_score(result) {
+            Ok(score) => self.completion_scores.extend(score),
+            Err(err) => {
+                self.completion_overflows += 1;
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// `None` if there are no incomplete lines, or some of their scores overflowed.
+    pub fn middle_completion_score(&self) -> Option<u64> {
+        if self.completion_overflows > 0 {
+            return None;
+        }
+        let mut scores = self.completion_scores.clone();
+        scores.sort_unstable();
+        scores.get(scores.len() / 2).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 10] = [
+        "[({(<(())[]>[[{[]{<()<>>",
+        "[(()[<>])]({[<{<<[]>>(",
+        "{([(<{}[<>[]}>{[]{[(<()>",
+        "(((({<>}<{<{<>}{[]{[]{}",
+        "[[<[([]))<([[{}[[()]]]",
+        "[{[{({}]{}}([{[{{{}}([]",
+        "{<[[]]>}<{[{[{[]{()[[[]",
+        "[<(<(<(<{}))><([]([]()",
+        "<{([([[(<>()){}]>(<<{{",
+        "<{([{{}}[<[[[<>{}]]]>[]]",
+    ];
+
+    #[test]
+    fn example() {
+        let language = Language::standard();
+        let mut scores = Scoreboard::default();
+        let lines = EXAMPLE.iter().map(|line| line.to_string());
+        for (_, _, result) in language.check_lines(lines) {
+            scores.add(&language, &result).unwrap();
+        }
+        assert_eq!(scores.error_total, 26397);
+        assert_eq!(scores.middle_completion_score(), Some(288957));
+        let expected = LineResult::Corrupted{column: 13, expected: Some(']'), found: '}'};
+        assert_eq!(language.check(EXAMPLE[2]), expected);
+    }
+
+    #[test]
+    fn line_numbers() {
+        let language = Language::standard();
+        let lines = ["()", "", "", "(]", "", "[<"].iter().map(|line| line.to_string());
+        let numbers = language.check_lines(lines).map(|(n, _, _)| n).collect::<Vec<_>>();
+        assert_eq!(numbers, vec![1, 4, 6]);
+    }
+
+    #[test]
+    fn overflow() {
+        let language = Language::standard();
+        let mut scores = Scoreboard::default();
+        // n closers > score 5^n - 1, which fits in a u64 up to n = 27
+        scores.add(&language, &language.check(&"<".repeat(27))).unwrap();
+        assert!(scores.add(&language, &language.check(&"<".repeat(28))).is_err());
+        assert_eq!(scores.completion_scores.len(), 1);
+        assert_eq!(scores.middle_completion_score(), None);
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub name: String,
+    pub large: bool,
+    pub links: Vec<usize>,
+    small_bit: u64, // 0 for large caves
+}
+
+#[derive(Debug, Clone)]
+pub struct Graph {
+    pub vertices: Vec<Node>,
+    start: usize,
+    end: usize,
+}
+
+/// How often small caves other than start and end may be visited again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisitPolicy {
+    None,
+    OneSmallTwice,
+    /// K revisits in total, to the same or different small caves
+    UpTo(u32),
+}
+
+impl RevisitPolicy {
+    fn revisits(&self) -> u32 {
+        match self {
+            RevisitPolicy::None => 0,
+            RevisitPolicy::OneSmallTwice => 1,
+            RevisitPolicy::UpTo(k) => *k,
+        }
+    }
+}
+
+impl Graph {
+    pub fn parse(lines: &[String]) -> Result<Graph, String> {
+        let mut vertices: Vec<Node> = vec![];
+        let node_index = |name: &str, vertices: &mut Vec<Node>| {
+            vertices.iter().position(|v| v.name == name).unwrap_or_else(|| {
+                vertices.push(Node{name: name.to_string(), large: name.chars().all(char::is_uppercase), links: vec![], small_bit: 0});
+                vertices.len() - 1
+            })
+        };
+        for line in lines {
+            let (from, to) = line.trim().split_once('-').ok_or(format!("Invalid edge: {}", line))?;
+            let (from, to) = (node_index(from, &mut vertices), node_index(to, &mut vertices));
+            if vertices[from].large && vertices[to].large {
+                return Err(format!("Infinitely many paths between {} and {}", vertices[from].name, vertices[to].name));
+            }
+            vertices[from].links.push(to);
+            vertices[to].links.push(from);
+        }
+        let small = vertices.iter_mut().filter(|v| !v.large).collect::<Vec<_>>();
+        if small.len() > 64 {
+            return Err(format!("Too many small caves ({})", small.len()));
+        }
+        for (i, v) in small.into_iter().enumerate() {
+            v.small_bit = 1 << i;
+        }
+        let find = |name: &str| vertices.iter().position(|v| v.name == name).ok_or(format!("No {} cave", name));
+        let (start, end) = (find("start")?, find("end")?);
+        Ok(Graph{vertices, start, end})
+    }
+
+    // caves that can be entered from `from`, and whether that uses up a revisit
+    fn next<'a>(&'a self, from: usize, visited: u64, revisits: u32) -> impl Iterator<Item = (usize, bool)> + 'a {
+        self.vertices[from].links.iter()
+            .filter(move |&&to| to != self.start)
+            .filter_map(move |&to| {
+                let again = visited & self.vertices[to].small_bit != 0;
+                (!again || revisits > 0).then_some((to, again))
+            })
+    }
+
+    /// Number of paths from start to end, without listing them.
+    pub fn count_paths(&self, policy: RevisitPolicy) -> u64 {
+        let mut cache = HashMap::new();
+        self.count_from(self.start, self.vertices[self.start].small_bit, policy.revisits(), &mut cache)
+    }
+
+    fn count_from(&self, from: usize, visited: u64, revisits: u32, cache: &mut HashMap<(usize, u64, u32), u64>) -> u64 {
+        if from == self.end {
+            return 1;
+        }
+        if let Some(&count) = cache.get(&(from, visited, revisits)) {
+            return count;
+        }
+        let count = self.next(from, visited, revisits)
+            .map(|(to, again)| self.count_from(to, visited | self.vertices[to].small_bit, revisits - again as u32, cache))
+            .sum();
+        cache.insert((from, visited, revisits), count);
+        count
+    }
+
+    /// Calls `visit` with every path from start to end.
+    pub fn for_each_path(&self, policy: RevisitPolicy, visit: &mut dyn FnMut(&[&str])) {
+        let mut path = vec![self.start];
+        self.walk(&mut path, self.vertices[self.start].small_bit, policy.revisits(), visit);
+    }
+
+    fn walk(&self, path: &mut Vec<usize>, visited: u64, revisits: u32, visit: &mut dyn FnMut(&[&str])) {
+        let from = *path.last().unwrap();
+        if from == self.end {
+            visit(&path.iter().map(|&i| self.vertices[i].name.as_str()).collect::<Vec<_>>());
+            return;
+        }
+        for (to, again) in self.next(from, visited, revisits).collect::<Vec<_>>() {
+            path.push(to);
+            self.walk(path, visited | self.vertices[to].small_bit, revisits - again as u32, visit);
+            path.pop();
+        }
+    }
+}
+
+impl std::fmt::Display for Graph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        write!(f, "Graph(nodes=[")?;
+        for node in self.vertices.iter() {
+            write!(f, "{},", node.name)?;
+        }
+        write!(f, "])")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: &str = "start-A start-b A-c A-b b-d A-end b-end";
+    const MEDIUM: &str = "dc-end HN-start start-kj dc-start dc-HN LN-dc HN-end kj-sa kj-HN kj-dc";
+    const LARGE: &str = "fs-end he-DX fs-he start-DX pj-DX end-zg zg-sl zg-pj pj-he RW-he fs-DX pj-RW zg-RW start-pj he-WI zg-he pj-fs start-RW";
+
+    fn parse(edges: &str) -> Result<Graph, String> {
+        Graph::parse(&edges.split_whitespace().map(String::from).collect::<Vec<_>>())
+    }
+
+    fn listed_paths(graph: &Graph, policy: RevisitPolicy) -> u64 {
+        let mut count = 0;
+        graph.for_each_path(policy, &mut |_| count += 1);
+        count
+    }
+
+    #[test]
+    fn examples() {
+        for (edges, none, once) in [(SMALL, 10, 36), (MEDIUM, 19, 103), (LARGE, 226, 3509)] {
+            let graph = parse(edges).unwrap();
+            assert_eq!(graph.count_paths(RevisitPolicy::None), none);
+            assert_eq!(graph.count_paths(RevisitPolicy::OneSmallTwice), once);
+            assert_eq!(graph.count_paths(RevisitPolicy::UpTo(1)), once);
+            assert_eq!(listed_paths(&graph, RevisitPolicy::OneSmallTwice), once);
+        }
+    }
+
+    #[test]
+    fn more_revisits() {
+        let graph = parse(SMALL).unwrap();
+        for k in 0..4 {
+            let policy = RevisitPolicy::UpTo(k);
+            assert_eq!(graph.count_paths(policy), listed_paths(&graph, policy), "{} revisits", k);
+        }
+        assert!(graph.count_paths(RevisitPolicy::UpTo(2)) > 36);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse("start-A A-B B-end").is_err());
+        assert!(parse("start-a a-b").is_err());
+        assert!(parse("start-a a-end").is_ok());
+    }
+}
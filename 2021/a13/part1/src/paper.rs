@@ -0,0 +1,169 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// Folds the points past the line onto the other side.
+    Fold(Axis, i32),
+    /// Quarter turns clockwise.
+    Rotate(i32),
+    /// Flips coordinates along the axis.
+    Mirror(Axis),
+}
+
+impl FromStr for Axis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "x" => Ok(Axis::X),
+            "y" => Ok(Axis::Y),
+            _ => Err(format!("Unknown axis {}", s)),
+        }
+    }
+}
+
+impl FromStr for Transform {
+    type Err = String;
+
+    // "fold along x=5", "rotate 1", "mirror along y"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s.split(|c: char| c.is_whitespace() || c == '=').collect::<Vec<_>>();
+        let number = |word: Option<&&str>| word.and_then(|w| w.parse().ok()).ok_or(format!("Missing number in {}", s));
+        match words.as_slice() {
+            ["fold", "along", axis, ..] => Ok(Transform::Fold(axis.parse()?, number(words.get(3))?)),
+            ["rotate", ..] => Ok(Transform::Rotate(number(words.get(1))?)),
+            ["mirror", "along", axis] => Ok(Transform::Mirror(axis.parse()?)),
+            _ => Err(format!("Unknown transform: {}", s)),
+        }
+    }
+}
+
+/// Sparse set of dots on the paper.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Paper {
+    points: BTreeSet<(i32, i32)>,
+}
+
+impl FromIterator<(i32, i32)> for Paper {
+    fn from_iter<I: IntoIterator<Item = (i32, i32)>>(iter: I) -> Self {
+        Paper{points: iter.into_iter().collect()}
+    }
+}
+
+impl Paper {
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        self.points.contains(&(x, y))
+    }
+
+    fn map(&self, f: impl Fn((i32, i32)) -> (i32, i32)) -> Paper {
+        self.points.iter().map(|&p| f(p)).collect()
+    }
+
+    // (min x, min y, max x, max y)
+    fn bounds(&self) -> Option<(i32, i32, i32, i32)> {
+        let first = *self.points.first()?;
+        Some(self.points.iter().fold((first.0, first.1, first.0, first.1), |(x0, y0, x1, y1), &(x, y)| {
+            (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+        }))
+    }
+
+    // moves the top left corner to the origin
+    fn normalised(&self) -> Paper {
+        match self.bounds() {
+            Some((x0, y0, _, _)) => self.map(|(x, y)| (x - x0, y - y0)),
+            None => self.clone(),
+        }
+    }
+
+    pub fn fold(&self, axis: Axis, at: i32) -> Paper {
+        let reflect = |v: i32| if v > at { 2 * at - v } else { v };
+        match axis {
+            Axis::X => self.map(|(x, y)| (reflect(x), y)),
+            Axis::Y => self.map(|(x, y)| (x, reflect(y))),
+        }
+    }
+
+    pub fn rotate(&self, quarter_turns: i32) -> Paper {
+        (0..quarter_turns.rem_euclid(4)).fold(self.clone(), |paper, _| paper.map(|(x, y)| (-y, x)).normalised())
+    }
+
+    pub fn mirror(&self, axis: Axis) -> Paper {
+        match axis {
+            Axis::X => self.map(|(x, y)| (-x, y)),
+            Axis::Y => self.map(|(x, y)| (x, -y)),
+        }.normalised()
+    }
+
+    pub fn apply(&self, transform: &Transform) -> Paper {
+        match *transform {
+            Transform::Fold(axis, at) => self.fold(axis, at),
+            Transform::Rotate(quarter_turns) => self.rotate(quarter_turns),
+            Transform::Mirror(axis) => self.mirror(axis),
+        }
+    }
+
+    /// Rows of the bounding box, `true` for dots.
+    pub fn plot(&self) -> Vec<Vec<bool>> {
+        let (x0, y0, x1, y1) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return vec![],
+        };
+        (y0..=y1).map(|y| (x0..=x1).map(|x| self.contains(x, y)).collect()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [(i32, i32); 18] = [
+        (6,10), (0,14), (9,10), (0,3), (10,4), (4,11), (6,0), (6,12), (4,1),
+        (0,13), (10,12), (3,4), (3,0), (8,4), (1,10), (2,14), (8,10), (9,0),
+    ];
+
+    fn plot(paper: &Paper) -> Vec<String> {
+        paper.plot().iter().map(|row| row.iter().map(|&dot| if dot { '#' } else { '.' }).collect()).collect()
+    }
+
+    #[test]
+    fn example() {
+        let paper = EXAMPLE.into_iter().collect::<Paper>();
+        let first = paper.apply(&"fold along y=7".parse().unwrap());
+        assert_eq!(first.len(), 17);
+        let second = first.apply(&"fold along x=5".parse().unwrap());
+        assert_eq!(second.len(), 16);
+        assert_eq!(plot(&second), ["#####", "#...#", "#...#", "#...#", "#####"]);
+    }
+
+    #[test]
+    fn rotate_and_mirror() {
+        // an L
+        let paper = [(0,0), (0,1), (0,2), (1,2)].into_iter().collect::<Paper>();
+        assert_eq!(plot(&paper.rotate(1)), ["###", "#.."]);
+        assert_eq!(plot(&paper.rotate(-1)), ["..#", "###"]);
+        assert_eq!(paper.rotate(4), paper);
+        assert_eq!(plot(&paper.mirror(Axis::X)), [".#", ".#", "##"]);
+        assert_eq!(plot(&paper.mirror(Axis::Y)), ["##", "#.", "#."]);
+        assert_eq!(paper.rotate(2), paper.mirror(Axis::X).mirror(Axis::Y));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("fold along x=5".parse(), Ok(Transform::Fold(Axis::X, 5)));
+        assert_eq!("rotate -1".parse(), Ok(Transform::Rotate(-1)));
+        assert_eq!("mirror along y".parse(), Ok(Transform::Mirror(Axis::Y)));
+        assert!("fold along z=5".parse::<Transform>().is_err());
+        assert!("rotate".parse::<Transform>().is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use matrix::Matrix;
+use num_bigint::BigUint;
+use num_traits::{CheckedAdd, CheckedMul, One, Zero};
+
+#[derive(Debug)]
+pub struct Polymer {
+    pub elements: Vec<char>,
+    pub template: Vec<usize>, // element indices
+    rules: HashMap<[usize;2], usize>,
+}
+
+impl Polymer {
+    pub fn parse(lines: &[String]) -> Result<Polymer, String> {
+        let mut elements = vec![];
+        let index = |c: char, elements: &mut Vec<char>| {
+            if !c.is_ascii_uppercase() {
+                return Err(format!("Invalid element {:?}", c));
+            }
+            Ok(elements.iter().position(|&e| e == c).unwrap_or_else(|| { elements.push(c); elements.len()-1 }))
+        };
+        let mut lines = lines.iter().filter(|line| !line.trim().is_empty());
+        let template = lines.next().ok_or("Missing template")?
+            .trim().chars().map(|c| index(c, &mut elements)).collect::<Result<Vec<_>,_>>()?;
+        if template.is_empty() {
+            return Err("Empty template".to_string());
+        }
+        let mut rules = HashMap::new();
+        for line in lines {
+            // "VF -> S"
+            let (pair, output) = line.split_once(" -> ").ok_or(format!("Invalid rule: {}", line))?;
+            let pair = pair.trim().chars().map(|c| index(c, &mut elements)).collect::<Result<Vec<_>,_>>()?;
+            let output = output.trim().chars().map(|c| index(c, &mut elements)).collect::<Result<Vec<_>,_>>()?;
+            if pair.len() != 2 || output.len() != 1 {
+                return Err(format!("Invalid rule: {}", line));
+            }
+            rules.insert([pair[0], pair[1]], output[0]);
+        }
+        Ok(Polymer{elements, template, rules})
+    }
+
+    fn pair_index(&self, a: usize, b: usize) -> usize {
+        a * self.elements.len() + b
+    }
+
+    /// Rule AB -> C turns each AB pair into an AC and a CB pair, so a step is linear in
+    /// the pair counts: entry (to, from) is how many `to` pairs one `from` pair becomes.
+    pub fn transition<T: Clone + Zero + One + CheckedAdd + CheckedMul>(&self) -> Matrix<T> {
+        let n = self.elements.len();
+        let mut m: Matrix<T> = Matrix::zero(n*n);
+        for a in 0..n {
+            for b in 0..n {
+                let from = self.pair_index(a, b);
+                let targets = match self.rules.get(&[a, b]) {
+                    Some(&c) => vec![self.pair_index(a, c), self.pair_index(c, b)],
+                    None => vec![from],
+                };
+                for to in targets {
+                    let cell = m.get_mut(to, from);
+                    *cell = cell.clone() + T::one();
+                }
+            }
+        }
+        m
+    }
+
+    fn checked_counts<T: Clone + Zero + One + CheckedAdd + CheckedMul>(&self, steps: u64) -> Option<Vec<T>> {
+        let n = self.elements.len();
+        let mut pairs = vec![T::zero(); n*n];
+        for w in self.template.windows(2) {
+            let p = self.pair_index(w[0], w[1]);
+            pairs[p] = pairs[p].clone() + T::one();
+        }
+        let pairs = self.transition::<T>().checked_pow(steps)?.checked_apply(&pairs)?;
+        // every element starts a pair, except the last one which never changes
+        let mut counts = vec![T::zero(); n];
+        let last = *self.template.last().unwrap();
+        counts[last] = T::one();
+        for (pair, count) in pairs.iter().enumerate() {
+            counts[pair / n] = counts[pair / n].checked_add(count)?;
+        }
+        Some(counts)
+    }
+
+    /// Element counts after `steps`, computed with `u64` and with big integers if that overflows.
+    pub fn element_counts(&self, steps: u64) -> Vec<(char, BigUint)> {
+        let counts = match self.checked_counts::<u64>(steps) {
+            Some(counts) => counts.into_iter().map(BigUint::from).collect(),
+            None => self.checked_counts::<BigUint>(steps).unwrap(),
+        };
+        self.elements.iter().copied().zip(counts).collect()
+    }
+
+
+    // builds the whole polymer, for checking
+    #[cfg(test)]
+    fn expand(&self, steps: u64) -> Vec<usize> {
+        let mut polymer = self.template.clone();
+        for _ in 0..steps {
+            let mut next = vec![polymer[0]];
+            for w in polymer.windows(2) {
+                if let Some(&c) = self.rules.get(&[w[0], w[1]]) {
+                    next.push(c);
+                }
+                next.push(w[1]);
+            }
+            polymer = next;
+        }
+        polymer
+    }
+}
+
+/// Most common minus least common count among the elements present.
+pub fn score(counts: &[(char, BigUint)]) -> BigUint {
+    let present = counts.iter().map(|(_, count)| count).filter(|c| !c.is_zero());
+    present.clone().max().unwrap() - present.min().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::polymer::{score, Polymer};
+    use num_bigint::BigUint;
+
+    const EXAMPLE: &str = "NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C";
+
+    fn example() -> Polymer {
+        Polymer::parse(&EXAMPLE.lines().map(String::from).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn matches_expansion() {
+        let polymer = example();
+        for steps in 0..=12 {
+            let expanded = polymer.expand(steps);
+            for (i, (_, count)) in polymer.element_counts(steps).into_iter().enumerate() {
+                let expected = expanded.iter().filter(|&&e| e == i).count();
+                assert_eq!(count, BigUint::from(expected), "step {} element {}", steps, i);
+            }
+        }
+    }
+
+    #[test]
+    fn example_scores() {
+        let polymer = example();
+        assert_eq!(score(&polymer.element_counts(10)), BigUint::from(1588u32));
+        assert_eq!(score(&polymer.element_counts(40)), BigUint::from(2188189693529u64));
+    }
+
+    #[test]
+    fn big_counts() {
+        let polymer = example();
+        assert_eq!(polymer.checked_counts::<u64>(100), None);
+        // every pair has a rule, so the length doubles minus one each step
+        let total: BigUint = polymer.element_counts(100).into_iter().map(|(_, count)| count).sum();
+        assert_eq!(total, BigUint::from(3u32) * (BigUint::from(1u32) << 100) + BigUint::from(1u32));
+    }
+}
@@ -0,0 +1,203 @@
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/*
+Without drag, after t steps a velocity v has moved v*t - t*(t-1)/2, a parabola
+in t. The steps where the probe is within a range are where that parabola is
+at least the low end, minus where it is above the high end. Along x the probe
+stops at t = |v| and stays put from then on.
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Area {
+    pub x: RangeInclusive<i64>,
+    pub y: RangeInclusive<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub count: usize,
+    pub max_height: i64,
+    pub highest: (i64, i64), // velocity reaching max_height
+}
+
+// steps are inclusive intervals, i64::MAX for forever
+type Steps = Vec<(i64, i64)>;
+
+impl FromStr for Area {
+    type Err = String;
+
+    // "target area: x=20..30, y=-10..-5"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = |axis: &str| -> Result<RangeInclusive<i64>, String> {
+            let start = s.find(&format!("{}=", axis)).ok_or(format!("Missing {} range", axis))? + 2;
+            let text = s[start..].split(',').next().unwrap().trim();
+            let (a, b) = text.split_once("..").ok_or(format!("Invalid {} range: {}", axis, text))?;
+            match (a.parse::<i64>(), b.parse::<i64>()) {
+                (Ok(a), Ok(b)) => Ok(a.min(b)..=a.max(b)),
+                _ => Err(format!("Invalid {} range: {}", axis, text)),
+            }
+        };
+        Ok(Area{x: range("x")?, y: range("y")?})
+    }
+}
+
+fn distance(v: i64, t: i64) -> i64 {
+    v*t - t*(t-1)/2
+}
+
+// steps t >= 1 where distance(v, t) >= min, closest to the peak at v+½
+fn steps_at_least(v: i64, min: i64) -> Option<(i64, i64)> {
+    // t² - (2v+1)t + 2min <= 0
+    let b = 2*v + 1;
+    let disc = b*b - 8*min;
+    if disc < 0 {
+        return None;
+    }
+    let root = (disc as f64).sqrt();
+    let (mut lo, mut hi) = (((b as f64 - root) / 2.0).ceil() as i64, ((b as f64 + root) / 2.0).floor() as i64);
+    // correct float rounding
+    while lo <= v && distance(v, lo) < min { lo += 1; }
+    while lo > 1 && distance(v, lo-1) >= min { lo -= 1; }
+    while hi > v && distance(v, hi) < min { hi -= 1; }
+    while distance(v, hi+1) >= min { hi += 1; }
+    let lo = lo.max(1);
+    (lo <= hi && distance(v, lo) >= min).then_some((lo, hi))
+}
+
+// steps where distance(v, t) is within range
+fn steps_within(v: i64, range: &RangeInclusive<i64>) -> Steps {
+    let inside = match steps_at_least(v, *range.start()) {
+        Some(inside) => inside,
+        None => return vec![],
+    };
+    match steps_at_least(v, *range.end() + 1) {
+        None => vec![inside],
+        Some((over_lo, over_hi)) => [(inside.0, inside.1.min(over_lo-1)), (inside.0.max(over_hi+1), inside.1)]
+            .into_iter().filter(|(lo, hi)| lo <= hi).collect(),
+    }
+}
+
+fn steps_x(vx: i64, range: &RangeInclusive<i64>) -> Steps {
+    if vx < 0 {
+        return steps_x(-vx, &(-range.end()..=-range.start()));
+    }
+    let rest = distance(vx, vx);
+    let mut steps = steps_within(vx, range).into_iter()
+        .filter(|&(lo, _)| lo <= vx.max(1))
+        .map(|(lo, hi)| (lo, hi.min(vx)))
+        .collect::<Steps>();
+    if range.contains(&rest) {
+        steps.push((vx.max(1), i64::MAX));
+    }
+    steps
+}
+
+fn steps_y(vy: i64, range: &RangeInclusive<i64>) -> Steps {
+    steps_within(vy, range)
+}
+
+fn overlap(a: &Steps, b: &Steps) -> bool {
+    a.iter().any(|&(lo1, hi1)| b.iter().any(|&(lo2, hi2)| lo1 <= hi2 && lo2 <= hi1))
+}
+
+impl Area {
+    /// x velocities that reach the target's columns at some point.
+    pub fn find_vx(&self) -> RangeInclusive<i64> {
+        let (a, b) = (*self.x.start(), *self.x.end());
+        // slowest velocity that comes to rest at or past n
+        let slowest = |n: i64| (0..).find(|&v| distance(v, v) >= n).unwrap();
+        if a > 0 {
+            slowest(a)..=b
+        } else if b < 0 {
+            a..=-slowest(-b)
+        } else {
+            a..=b
+        }
+    }
+
+    /// y velocities that can reach the target's rows.
+    pub fn find_vy(&self) -> Result<RangeInclusive<i64>, String> {
+        let (a, b) = (*self.y.start(), *self.y.end());
+        if b < 0 {
+            // comes back down to 0 with -vy-1, which must not skip the target
+            Ok(a..=-a-1)
+        } else if a > 0 {
+            // must peak at or above a, and not jump past b on the first step
+            Ok((0..).find(|&v| distance(v, v) >= a).unwrap()..=b)
+        } else {
+            Err("Target contains y=0, any high enough shot comes back down into it".to_string())
+        }
+    }
+
+    pub fn solve(&self) -> Result<Solution, String> {
+        let vys = self.find_vy()?;
+        let x_steps = self.find_vx().map(|vx| (vx, steps_x(vx, &self.x))).filter(|(_, s)| !s.is_empty()).collect::<Vec<_>>();
+        let mut solution = Solution{count: 0, max_height: i64::MIN, highest: (0, 0)};
+        for vy in vys {
+            let y_steps = steps_y(vy, &self.y);
+            for (vx, x_steps) in x_steps.iter() {
+                if overlap(x_steps, &y_steps) {
+                    solution.count += 1;
+                    let height = distance(vy, vy.max(0));
+                    if height > solution.max_height {
+                        solution.max_height = height;
+                        solution.highest = (*vx, vy);
+                    }
+                }
+            }
+        }
+        if solution.count == 0 {
+            return Err("No shot reaches the target".to_string());
+        }
+        Ok(solution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::probe::*;
+
+    // the step by step rules from the puzzle
+    fn simulate(vx: i64, vy: i64, target: &Area) -> Option<i64> {
+        let (mut x, mut y, mut vx, mut vy, mut top) = (0, 0, vx, vy, 0);
+        for _ in 0..500 {
+            x += vx;
+            y += vy;
+            vx -= vx.signum();
+            vy -= 1;
+            top = top.max(y);
+            if target.x.contains(&x) && target.y.contains(&y) {
+                return Some(top);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn example() {
+        let target: Area = "target area: x=20..30, y=-10..-5".parse().unwrap();
+        let solution = target.solve().unwrap();
+        assert_eq!(solution.max_height, 45);
+        assert_eq!(solution.count, 112);
+    }
+
+    #[test]
+    fn matches_simulation() {
+        for target in ["x=20..30, y=-10..-5", "x=-30..-20, y=-10..-5", "x=20..30, y=5..10", "x=-30..-20, y=15..17", "x=-3..4, y=-10..-5", "x=7..7, y=-1..-1"] {
+            let target: Area = target.parse().unwrap();
+            let hits = (-40..=40).flat_map(|vx| (-40..=40).map(move |vy| (vx, vy)))
+                .filter_map(|(vx, vy)| simulate(vx, vy, &target))
+                .collect::<Vec<_>>();
+            let solution = target.solve().unwrap();
+            assert_eq!(solution.count, hits.len(), "{:?}", target);
+            assert_eq!(solution.max_height, *hits.iter().max().unwrap(), "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn straddling_target() {
+        let target: Area = "target area: x=20..30, y=-5..5".parse().unwrap();
+        assert!(target.solve().is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::{HashMap, HashSet};
+
+/*
+The image is split in 64x64 tiles, each row of a tile is one u64 with the
+leftmost pixel in the most significant bit. Tiles that are all background are
+not stored, so the image is infinite in every direction. The algorithm can turn
+the background on and off (when its first entry is lit), which flips the
+meaning of the missing tiles.
+*/
+
+const TILE: i32 = 64;
+
+pub type Algorithm = [bool; 512];
+
+pub type Tile = [u64; TILE as usize];
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    tiles: HashMap<(i32, i32), Tile>,
+    pub background: bool,
+}
+
+pub fn parse_algorithm(line: &str) -> Result<Algorithm, String> {
+    let bits = line.trim().chars().map(|c| match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err(format!("Invalid pixel {:?}", c)),
+    }).collect::<Result<Vec<_>,_>>()?;
+    bits.try_into().map_err(|bits: Vec<bool>| format!("Algorithm has {} entries instead of 512", bits.len()))
+}
+
+impl Image {
+    pub fn parse(lines: &[String]) -> Result<Image, String> {
+        let mut image = Image{tiles: HashMap::new(), background: false};
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => image.set(x as i32, y as i32),
+                    '.' => (),
+                    _ => return Err(format!("Invalid pixel {:?}", c)),
+                }
+            }
+        }
+        Ok(image)
+    }
+
+    fn background_word(&self) -> u64 {
+        if self.background { u64::MAX } else { 0 }
+    }
+
+    fn set(&mut self, x: i32, y: i32) {
+        let background = self.background_word();
+        let tile = self.tiles.entry((x.div_euclid(TILE), y.div_euclid(TILE))).or_insert([background; TILE as usize]);
+        tile[y.rem_euclid(TILE) as usize] |= 1 << (TILE - 1 - x.rem_euclid(TILE));
+    }
+
+    pub fn pixel(&self, x: i32, y: i32) -> bool {
+        (self.word(x.div_euclid(TILE), y.div_euclid(TILE) * TILE + y.rem_euclid(TILE)) >> (TILE - 1 - x.rem_euclid(TILE))) & 1 == 1
+    }
+
+    // row `y` (in pixels) of the tiles in column `tx`
+    fn word(&self, tx: i32, y: i32) -> u64 {
+        match self.tiles.get(&(tx, y.div_euclid(TILE))) {
+            Some(tile) => tile[y.rem_euclid(TILE) as usize],
+            None => self.background_word(),
+        }
+    }
+
+    /// Lit pixels, or `None` when the background is lit and there are infinitely many.
+    pub fn count_lit(&self) -> Option<usize> {
+        if self.background {
+            return None;
+        }
+        Some(self.tiles.values().flat_map(|tile| tile.iter()).map(|w| w.count_ones() as usize).sum())
+    }
+
+    pub fn enhance(&self, algo: &Algorithm) -> Image {
+        let background = algo[if self.background { 511 } else { 0 }];
+        let mut image = Image{tiles: HashMap::new(), background};
+        // tiles next to stored ones may change too
+        let candidates = self.tiles.keys()
+            .flat_map(|&(tx, ty)| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| (tx + dx, ty + dy))))
+            .collect::<HashSet<_>>();
+        for (tx, ty) in candidates {
+            let tile = self.enhance_tile(algo, tx, ty);
+            if tile.iter().any(|&w| w != image.background_word()) {
+                image.tiles.insert((tx, ty), tile);
+            }
+        }
+        image
+    }
+
+    fn enhance_tile(&self, algo: &Algorithm, tx: i32, ty: i32) -> Tile {
+        // a row with one pixel from each neighbouring tile, as 66 bits
+        let extended = |y: i32| -> u128 {
+            let left = self.word(tx - 1, y) & 1;
+            let right = self.word(tx + 1, y) >> (TILE - 1);
+            ((left as u128) << (TILE + 1)) | ((self.word(tx, y) as u128) << 1) | right as u128
+        };
+        let mut tile = [0; TILE as usize];
+        for (row, word) in tile.iter_mut().enumerate() {
+            let y = ty * TILE + row as i32;
+            let (above, here, below) = (extended(y - 1), extended(y), extended(y + 1));
+            for x in 0..TILE {
+                let shift = TILE - 1 - x;
+                let index = ((above >> shift) & 7) << 6 | ((here >> shift) & 7) << 3 | ((below >> shift) & 7);
+                if algo[index as usize] {
+                    *word |= 1 << shift;
+                }
+            }
+        }
+        tile
+    }
+
+    // pixels that are stored, as (min x, min y, max x, max y)
+    fn bounds(&self) -> Option<(i32, i32, i32, i32)> {
+        let min_x = self.tiles.keys().map(|t| t.0).min()?;
+        let min_y = self.tiles.keys().map(|t| t.1).min()?;
+        let max_x = self.tiles.keys().map(|t| t.0).max()?;
+        let max_y = self.tiles.keys().map(|t| t.1).max()?;
+        Some((min_x * TILE, min_y * TILE, (max_x + 1) * TILE - 1, (max_y + 1) * TILE - 1))
+    }
+}
+
+impl std::fmt::Display for Image {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        let (x0, y0, x1, y1) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return writeln!(f, "{}", if self.background { "#" } else { "." }),
+        };
+        for y in y0..=y1 {
+            let line = (x0..=x1).map(|x| if self.pixel(x, y) { '#' } else { '.' }).collect::<String>();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lit pixel counts after each of `generations`, in order.
+pub fn lit_counts(image: &Image, algo: &Algorithm, generations: &[usize]) -> Vec<(usize, Option<usize>)> {
+    let mut image = image.clone();
+    let mut generation = 0;
+    let mut counts = vec![];
+    let mut wanted = generations.to_vec();
+    wanted.sort_unstable();
+    for target in wanted {
+        while generation < target {
+            image = image.enhance(algo);
+            generation += 1;
+        }
+        counts.push((generation, image.count_lit()));
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::*;
+
+    const EXAMPLE_ALGORITHM: &str = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#";
+
+    fn image(rows: &[&str]) -> Image {
+        Image::parse(&rows.iter().map(|r| r.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    // one pixel at a time, within a margin around the original image
+    fn naive(algo: &Algorithm, rows: &[&str], generations: usize) -> usize {
+        let margin = generations as i32 + 2;
+        let (w, h) = (rows[0].len() as i32, rows.len() as i32);
+        let mut pixels = (-margin..h + margin).map(|y| (-margin..w + margin).map(|x| {
+            (0..w).contains(&x) && (0..h).contains(&y) && rows[y as usize].as_bytes()[x as usize] == b'#'
+        }).collect::<Vec<_>>()).collect::<Vec<_>>();
+        let mut background = false;
+        for _ in 0..generations {
+            let get = |x: i32, y: i32| pixels.get(y as usize).and_then(|row| row.get(x as usize)).copied().unwrap_or(background);
+            pixels = (0..pixels.len() as i32).map(|y| (0..pixels[0].len() as i32).map(|x| {
+                let index = (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (dx, dy))).fold(0, |acc, (dx, dy)| acc << 1 | get(x + dx, y + dy) as usize);
+                algo[index]
+            }).collect()).collect();
+            background = algo[if background { 511 } else { 0 }];
+        }
+        pixels.iter().flatten().filter(|&&p| p).count()
+    }
+
+    #[test]
+    fn example() {
+        let algo = parse_algorithm(EXAMPLE_ALGORITHM).unwrap();
+        let image = image(&["#..#.", "#....", "##..#", "..#..", "..###"]);
+        assert_eq!(lit_counts(&image, &algo, &[50, 2]), vec![(2, Some(35)), (50, Some(3351))]);
+    }
+
+    #[test]
+    fn flipping_background() {
+        // lights up the empty background, and turns it off again
+        let mut algo = parse_algorithm(EXAMPLE_ALGORITHM).unwrap();
+        algo[0] = true;
+        algo[511] = false;
+        let rows = ["#..#.", "#....", "##..#", "..#..", "..###"];
+        let image = image(&rows);
+        for generation in [1, 2, 3, 4, 30, 31] {
+            let count = lit_counts(&image, &algo, &[generation])[0].1;
+            if generation % 2 == 1 {
+                assert_eq!(count, None);
+            } else {
+                assert_eq!(count, Some(naive(&algo, &rows, generation)), "generation {}", generation);
+            }
+        }
+    }
+
+    #[test]
+    fn across_tiles() {
+        let algo = parse_algorithm(EXAMPLE_ALGORITHM).unwrap();
+        let rows = ["#..#.".repeat(30), "#....".repeat(30), "##..#".repeat(30), "..#..".repeat(30), "..###".repeat(30)];
+        let rows = rows.iter().map(String::as_str).collect::<Vec<_>>();
+        let rows = rows.iter().cycle().take(140).copied().collect::<Vec<_>>();
+        assert_eq!(lit_counts(&image(&rows), &algo, &[3])[0].1, Some(naive(&algo, &rows, 3)));
+    }
+}
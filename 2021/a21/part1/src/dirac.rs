@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+#[derive(Debug,Copy,Clone,Hash,Eq,PartialEq)]
+pub struct Player {
+    pub pos: u32,
+    pub score: u32
+}
+
+#[derive(Debug,Copy,Clone)]
+pub struct Game {
+    pub board_size: u32,
+    pub die_sides: u32,
+    pub rolls_per_turn: u32,
+    pub winning_score: u32,
+}
+
+pub const DETERMINISTIC: Game = Game{board_size: 10, die_sides: 100, rolls_per_turn: 3, winning_score: 1000};
+pub const QUANTUM: Game = Game{board_size: 10, die_sides: 3, rolls_per_turn: 3, winning_score: 21};
+
+impl Player {
+    pub fn new(pos: u32) -> Player {
+        Player{pos, score: 0}
+    }
+
+    pub fn parse(line: &str) -> Option<Player> {
+        // Player 1 starting position: 4
+        let pos = line.strip_prefix("Player ")?.split(": ").nth(1)?.trim().parse().ok()?;
+        Some(Player::new(pos))
+    }
+
+    fn roll(&self, roll: u32, board_size: u32) -> Player {
+        let pos = (self.pos - 1 + roll) % board_size + 1;
+        Player{pos, score: self.score+pos}
+    }
+}
+
+impl std::fmt::Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(f,"[pos:{},score:{}]", self.pos, self.score)?;
+        Ok(())
+    }
+}
+
+impl Game {
+    /// (occurrences, roll) for every total of one turn's rolls
+    pub fn roll_distribution(&self) -> Vec<(u128,u32)> {
+        let mut totals: HashMap<u32,u128> = HashMap::from([(0, 1)]);
+        for _ in 0..self.rolls_per_turn {
+            let mut next = HashMap::new();
+            for (total, times) in totals {
+                for side in 1..=self.die_sides {
+                    *next.entry(total + side).or_insert(0) += times;
+                }
+            }
+            totals = next;
+        }
+        let mut rolls = totals.into_iter().map(|(roll, times)| (times, roll)).collect::<Vec<_>>();
+        rolls.sort_by_key(|&(_, roll)| roll);
+        rolls
+    }
+
+    /// Plays with a die that rolls 1, 2, 3... in order, returns the
+    /// winner's index and the final players and number of dice rolls.
+    pub fn play_deterministic(&self, players: &[Player]) -> (usize, Vec<Player>, u32) {
+        let mut players = players.to_vec();
+        let mut next_roll = 0;
+        let mut dice_rolls = 0u32;
+        for cur_player in (0..players.len()).cycle() {
+            let mut roll = 0;
+            for _ in 0..self.rolls_per_turn {
+                roll += next_roll + 1;
+                next_roll = (next_roll + 1) % self.die_sides;
+            }
+            dice_rolls += self.rolls_per_turn;
+            players[cur_player] = players[cur_player].roll(roll, self.board_size);
+            if players[cur_player].score >= self.winning_score {
+                return (cur_player, players, dice_rolls);
+            }
+        }
+        unreachable!()
+    }
+
+    /// Counts the universes in which each player wins.
+    pub fn play_quantum(&self, player1: &Player, player2: &Player) -> (u128, u128) {
+        let mut cache = HashMap::new();
+        play(player1, player2, self, &self.roll_distribution(), &mut cache)
+    }
+}
+
+fn play(player1: &Player, player2: &Player, game: &Game, rolls: &[(u128,u32)], cache: &mut HashMap<[Player;2],(u128,u128)>) -> (u128, u128) {
+    let (mut wins1, mut wins2) = (0, 0);
+    let key = [*player1, *player2];
+    if let Some(cached) = cache.get(&key) {
+        return *cached;
+    }
+    for (times1, roll1) in rolls {
+        let player1 = player1.roll(*roll1, game.board_size);
+        if player1.score >= game.winning_score {
+            wins1 += times1;
+            continue;
+        }
+        for (times2, roll2) in rolls {
+            let player2 = player2.roll(*roll2, game.board_size);
+            if player2.score >= game.winning_score {
+                wins2 += times1 * times2;
+            } else {
+                let sub_wins = play(&player1, &player2, game, rolls, cache);
+                wins1 += times1 * times2 * sub_wins.0;
+                wins2 += times1 * times2 * sub_wins.1;
+            }
+        }
+    }
+    cache.insert(key, (wins1, wins2));
+    (wins1, wins2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> [Player; 2] {
+        [Player::parse("Player 1 starting position: 4").unwrap(), Player::parse("Player 2 starting position: 8").unwrap()]
+    }
+
+    #[test]
+    fn example_deterministic() {
+        let (winner, players, dice_rolls) = DETERMINISTIC.play_deterministic(&example());
+        assert_eq!(winner, 0);
+        assert_eq!((players[0].score, players[1].score, dice_rolls), (1000, 745, 993));
+    }
+
+    #[test]
+    fn example_quantum() {
+        let [player1, player2] = example();
+        assert_eq!(QUANTUM.play_quantum(&player1, &player2), (444356092776315, 341960390180808));
+    }
+
+    #[test]
+    fn roll_distribution() {
+        let rolls = QUANTUM.roll_distribution();
+        assert_eq!(rolls, vec![(1, 3), (3, 4), (6, 5), (7, 6), (6, 7), (3, 8), (1, 9)]);
+        let coin = Game{die_sides: 2, rolls_per_turn: 1, ..QUANTUM};
+        assert_eq!(coin.roll_distribution(), vec![(1, 1), (1, 2)]);
+    }
+}
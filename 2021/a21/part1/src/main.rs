@@ -1,48 +1,68 @@
+mod dirac;
 
-#[derive(Debug)]
-struct Player {
-    pos: u32,
-    score: u32
+use std::io::{self, BufRead};
+use crate::dirac::*;
+
+fn read_input(board_size: u32) -> Vec<Player> {
+    io::stdin().lock().lines()
+        .map_while(Result::ok)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let player = Player::parse(&line).unwrap_or_else(|| panic!("Invalid line: {}", line));
+            if player.pos < 1 || player.pos > board_size {
+                eprintln!("Invalid start position {}, expected 1 to {}", player.pos, board_size);
+                std::process::exit(1);
+            }
+            player
+        })
+        .collect()
 }
 
-impl Player {
-    fn roll(&mut self, values: &[u8]) {
-        let roll = values.iter().fold(0u32, |acc,n| acc+(*n as u32));
-        self.pos += roll;
-        while self.pos > 10 {
-            self.pos -= 10
+fn read_options() -> (Game, Game) {
+    let (mut deterministic, mut quantum) = (DETERMINISTIC, QUANTUM);
+    let args = std::env::args().collect::<Vec<_>>();
+    for pair in args[1..].chunks(2) {
+        let value: u32 = match pair.get(1).map(|v| v.parse()) {
+            Some(Ok(value)) if value > 0 => value,
+            _ => {
+                eprintln!("Usage: {} [--board N] [--rolls N] [--die N] [--goal N] [--quantum-die N] [--quantum-goal N]", args[0]);
+                std::process::exit(1);
+            }
+        };
+        match pair[0].as_str() {
+            "--board" => { deterministic.board_size = value; quantum.board_size = value },
+            "--rolls" => { deterministic.rolls_per_turn = value; quantum.rolls_per_turn = value },
+            "--die" => deterministic.die_sides = value,
+            "--goal" => deterministic.winning_score = value,
+            "--quantum-die" => quantum.die_sides = value,
+            "--quantum-goal" => quantum.winning_score = value,
+            arg => {
+                eprintln!("Unknown option {}", arg);
+                std::process::exit(1);
+            }
         }
-        self.score += self.pos;
     }
-}
-
-#[inline]
-fn wrap_101(value: u8) -> u8 {
-    if value > 100 { value - 100 } else { value }
+    (deterministic, quantum)
 }
 
 fn main() {
-    let mut players = [
-        Player{pos: 8, score: 0},
-        Player{pos: 4, score: 0}
-    ];
-    let mut next_roll = 1u8;
-    let mut cur_player = 0;
-    let mut dice_rolls = 0u32;
-    loop {
-        let rolls = [next_roll, wrap_101(next_roll+1), wrap_101(next_roll+2)];
-        dice_rolls += 3;
-        next_roll = wrap_101(next_roll + 3);
-        let player = &mut players[cur_player];
-        player.roll(&rolls);
-        println!("Player {} rolls {}+{}+{} and moves to space {} for a total score of {}", cur_player+1, rolls[0], rolls[1], rolls[2], player.pos, player.score);
-        if player.score >= 1000 {
-            println!("Player {} wins after {} dice rolls.", cur_player+1, dice_rolls);
-            let other_player_score = players[(cur_player + 1) % players.len()].score;
-            println!("Result is {}", other_player_score * dice_rolls);
-            dbg!(players);
-            break;
-        }
-        cur_player = (cur_player + 1) % players.len();
+    let (deterministic, quantum) = read_options();
+    let players = read_input(deterministic.board_size);
+    if players.len() != 2 {
+        eprintln!("Expected 2 players, got {}", players.len());
+        std::process::exit(1);
     }
+    for (i,p) in players.iter().enumerate() {
+        println!("Player {} starts at {}", i+1, p.pos);
+    }
+
+    let (winner, scores, dice_rolls) = deterministic.play_deterministic(&players);
+    println!("Player {} wins after {} dice rolls.", winner+1, dice_rolls);
+    let loser_score: u32 = scores.iter().enumerate().filter(|(i,_)| *i != winner).map(|(_,p)| p.score).sum();
+    println!("part 1: {}", loser_score * dice_rolls);
+
+    let wins = quantum.play_quantum(&players[0], &players[1]);
+    println!("Player 1 wins in {} universes", wins.0);
+    println!("Player 2 wins in {} universes", wins.1);
+    println!("part 2: {}", wins.0.max(wins.1));
 }
@@ -1,289 +1,63 @@
 use std::io::{self, BufRead};
 use std::cmp::Ordering;
-use std::collections::{HashMap,HashSet,BinaryHeap};
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-enum HallPosition {
-    P1, P2, P3, P4, P5, P6, P7
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-enum Kind {
-    Amber,
-    Bronze,
-    Copper,
-    Desert
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-enum Side {
-    Top,
-    Bottom,
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-#[repr(u8)]
-enum Position {
-    Hall(HallPosition),
-    Room(Kind, Side)
-}
-
+use std::collections::{HashMap,BinaryHeap};
 
 /*
+The burrow is read from the diagram, so any hallway length, number of rooms and
+room depth works. Rooms are assigned to A, B, C, ... from left to right.
+
 #############
-#12.3.4.5.67#
-###A#B#C#D###
-  #A#C#A#B#
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
   #########
 */
 
-const CONNECTIONS: [(Position, Position, u8); 18] = [
-    (Position::Hall(HallPosition::P1), Position::Hall(HallPosition::P2), 1),
-    (Position::Hall(HallPosition::P2), Position::Hall(HallPosition::P3), 2),
-    (Position::Hall(HallPosition::P3), Position::Hall(HallPosition::P4), 2),
-    (Position::Hall(HallPosition::P4), Position::Hall(HallPosition::P5), 2),
-    (Position::Hall(HallPosition::P5), Position::Hall(HallPosition::P6), 2),
-    (Position::Hall(HallPosition::P6), Position::Hall(HallPosition::P7), 1),
-
-    (Position::Hall(HallPosition::P2), Position::Room(Kind::Amber, Side::Top), 2),
-    (Position::Hall(HallPosition::P3), Position::Room(Kind::Amber, Side::Top), 2),
-
-    (Position::Hall(HallPosition::P3), Position::Room(Kind::Bronze, Side::Top), 2),
-    (Position::Hall(HallPosition::P4), Position::Room(Kind::Bronze, Side::Top), 2),
-    
-    (Position::Hall(HallPosition::P4), Position::Room(Kind::Copper, Side::Top), 2),
-    (Position::Hall(HallPosition::P5), Position::Room(Kind::Copper, Side::Top), 2),
-    
-    (Position::Hall(HallPosition::P5), Position::Room(Kind::Desert, Side::Top), 2),
-    (Position::Hall(HallPosition::P6), Position::Room(Kind::Desert, Side::Top), 2),
-    
-    (Position::Room(Kind::Amber, Side::Top), Position::Room(Kind::Amber, Side::Bottom), 1),
-    (Position::Room(Kind::Bronze, Side::Top), Position::Room(Kind::Bronze, Side::Bottom), 1),
-    (Position::Room(Kind::Copper, Side::Top), Position::Room(Kind::Copper, Side::Bottom), 1),
-    (Position::Room(Kind::Desert, Side::Top), Position::Room(Kind::Desert, Side::Bottom), 1),
-];
-
-const POSITIONS: [Position; 15] = [
-    Position::Hall(HallPosition::P1),
-    Position::Hall(HallPosition::P2),
-    Position::Hall(HallPosition::P3),
-    Position::Hall(HallPosition::P4),
-    Position::Hall(HallPosition::P5),
-    Position::Hall(HallPosition::P6),
-    Position::Hall(HallPosition::P7),
-    Position::Room(Kind::Amber, Side::Top),
-    Position::Room(Kind::Amber, Side::Bottom),
-    Position::Room(Kind::Bronze, Side::Top),
-    Position::Room(Kind::Bronze, Side::Bottom),
-    Position::Room(Kind::Copper, Side::Top),
-    Position::Room(Kind::Copper, Side::Bottom),
-    Position::Room(Kind::Desert, Side::Top),
-    Position::Room(Kind::Desert, Side::Bottom),
-];
-
-impl Default for Position {
-    fn default() -> Self {
-        Position::Room(Kind::Amber, Side::Top)
-    }
+const EMPTY: u8 = b'.';
+
+#[derive(Debug)]
+struct Room {
+    kind: u8,
+    // hallway cell just outside the room
+    door: usize,
+    // cells from top to bottom
+    cells: Vec<usize>,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-struct Amphipod {
-    kind: Kind,
-    index: usize,
+#[derive(Debug)]
+struct Burrow {
+    // hallway cells from left to right
+    hall: Vec<usize>,
+    rooms: Vec<Room>,
 }
 
-const AMPHIPODS: [Amphipod; 8] = [
-    // index maps to positions in State struct
-    Amphipod{kind: Kind::Amber, index: 0},
-    Amphipod{kind: Kind::Amber, index: 1},
-    Amphipod{kind: Kind::Bronze, index: 2},
-    Amphipod{kind: Kind::Bronze, index: 3},
-    Amphipod{kind: Kind::Copper, index: 4},
-    Amphipod{kind: Kind::Copper, index: 5},
-    Amphipod{kind: Kind::Desert, index: 6},
-    Amphipod{kind: Kind::Desert, index: 7},
-];
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 struct State {
-    energy: u32,
-    positions: [Position; 8], // A A B B C C D D
+    energy: u64,
+    width: usize,
+    cells: Vec<u8>,
 }
 
-impl std::fmt::Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        write!(f, "{}", match self {
-            Position::Hall(HallPosition::P1) => "Hall::P1",
-            Position::Hall(HallPosition::P2) => "Hall::P2",
-            Position::Hall(HallPosition::P3) => "Hall::P3",
-            Position::Hall(HallPosition::P4) => "Hall::P4",
-            Position::Hall(HallPosition::P5) => "Hall::P5",
-            Position::Hall(HallPosition::P6) => "Hall::P6",
-            Position::Hall(HallPosition::P7) => "Hall::P7",
-            Position::Room(Kind::Amber, Side::Top) => "Room::A::Top",
-            Position::Room(Kind::Amber, Side::Bottom) =>  "Room::A::Bottom",
-            Position::Room(Kind::Bronze, Side::Top) => "Room::B::Top",
-            Position::Room(Kind::Bronze, Side::Bottom) => "Room::B::Bottom",
-            Position::Room(Kind::Copper, Side::Top) => "Room::C::Top",
-            Position::Room(Kind::Copper, Side::Bottom) => "Room::C::Bottom",
-            Position::Room(Kind::Desert, Side::Top) => "Room::D::Top",
-            Position::Room(Kind::Desert, Side::Bottom) => "Room::D::Bottom",
-        })?;
-        Ok(())
-    }
+#[derive(Debug, Copy, Clone)]
+struct Move {
+    from: usize,
+    to: usize,
+    cost: u64,
 }
 
 impl std::fmt::Display for State {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        writeln!(f, "#############")?;
-        writeln!(f, "#{}{}.{}.{}.{}.{}{}#",
-            self.kind_in(Position::Hall(HallPosition::P1)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Hall(HallPosition::P2)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Hall(HallPosition::P3)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Hall(HallPosition::P4)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Hall(HallPosition::P5)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Hall(HallPosition::P6)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Hall(HallPosition::P7)).map(Kind::to_char).unwrap_or('.'),
-        )?;
-        writeln!(f, "###{}#{}#{}#{}###",
-            self.kind_in(Position::Room(Kind::Amber, Side::Top)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Room(Kind::Bronze, Side::Top)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Room(Kind::Copper, Side::Top)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Room(Kind::Desert, Side::Top)).map(Kind::to_char).unwrap_or('.'))?;
-        writeln!(f, "  #{}#{}#{}#{}#",
-            self.kind_in(Position::Room(Kind::Amber, Side::Bottom)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Room(Kind::Bronze, Side::Bottom)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Room(Kind::Copper, Side::Bottom)).map(Kind::to_char).unwrap_or('.'),
-            self.kind_in(Position::Room(Kind::Desert, Side::Bottom)).map(Kind::to_char).unwrap_or('.'))?;
-        writeln!(f, "  #########")?;
-        Ok(())
-    }
-}
-
-
-fn precompute_paths() -> HashMap<(Position,Position),(u32, Vec<Position>)> {
-    let mut paths = HashMap::new();
-    for p in POSITIONS {
-        for q in POSITIONS {
-            if p == q {
-                continue;
-            }
-            paths.insert((p,q), p.path(q, &paths));
-        }
-    }
-    paths
-}
-
-type PathCache = HashMap<(Position,Position),(u32, Vec<Position>)>;
-
-impl Position {
-    fn distance(&self, to: &Position) -> u32 {
-        for (a,b,d) in CONNECTIONS {
-            if a == *self && b == *to || a == *to && b == *self {
-                return d as u32;
-            }
-        }
-        panic!("no distance from {} to {}", self, to);
-    }
-
-    fn neighbours(&self) -> Vec<Position> {
-        match self {
-            Position::Hall(HallPosition::P1) => vec![Position::Hall(HallPosition::P2)],
-            Position::Hall(HallPosition::P2) => vec![Position::Hall(HallPosition::P1), Position::Hall(HallPosition::P3), Position::Room(Kind::Amber, Side::Top)],
-            Position::Hall(HallPosition::P3) => vec![Position::Hall(HallPosition::P2), Position::Hall(HallPosition::P4), Position::Room(Kind::Amber, Side::Top), Position::Room(Kind::Bronze, Side::Top)],
-            Position::Hall(HallPosition::P4) => vec![Position::Hall(HallPosition::P3), Position::Hall(HallPosition::P5), Position::Room(Kind::Bronze, Side::Top), Position::Room(Kind::Copper, Side::Top)],
-            Position::Hall(HallPosition::P5) => vec![Position::Hall(HallPosition::P4), Position::Hall(HallPosition::P6), Position::Room(Kind::Copper, Side::Top), Position::Room(Kind::Desert, Side::Top)],
-            Position::Hall(HallPosition::P6) => vec![Position::Hall(HallPosition::P5), Position::Hall(HallPosition::P7), Position::Room(Kind::Desert, Side::Top)],
-            Position::Hall(HallPosition::P7) => vec![Position::Hall(HallPosition::P6)],
-            Position::Room(Kind::Amber, Side::Top) => vec![Position::Hall(HallPosition::P2), Position::Hall(HallPosition::P3), Position::Room(Kind::Amber, Side::Bottom)],
-            Position::Room(Kind::Amber, Side::Bottom) => vec![Position::Room(Kind::Amber, Side::Top)],
-            Position::Room(Kind::Bronze, Side::Top) => vec![Position::Hall(HallPosition::P3), Position::Hall(HallPosition::P4), Position::Room(Kind::Bronze, Side::Bottom)],
-            Position::Room(Kind::Bronze, Side::Bottom) => vec![Position::Room(Kind::Bronze, Side::Top)],
-            Position::Room(Kind::Copper, Side::Top) => vec![Position::Hall(HallPosition::P4), Position::Hall(HallPosition::P5), Position::Room(Kind::Copper, Side::Bottom)],
-            Position::Room(Kind::Copper, Side::Bottom) => vec![Position::Room(Kind::Copper, Side::Top)],
-            Position::Room(Kind::Desert, Side::Top) => vec![Position::Hall(HallPosition::P5), Position::Hall(HallPosition::P6), Position::Room(Kind::Desert, Side::Bottom)],
-            Position::Room(Kind::Desert, Side::Bottom) => vec![Position::Room(Kind::Desert, Side::Top)],
-        }
-    }
-
-    fn path(&self, to: Position, cache: &PathCache) -> (u32, Vec<Position>) {
-        let from = *self;
-        if from == to {
-            return (0, vec![]);
-        }
-        if let Some(cached) = cache.get(&(from, to)) {
-            return cached.clone();
-        }
-        let mut queue: HashSet<Position> = HashSet::from_iter(POSITIONS.into_iter());
-        let mut dist: HashMap<Position, u32> = HashMap::new();
-        let mut prev: HashMap<Position, Position> = HashMap::new();
-        dist.insert(from, 0);
-        prev.insert(from, from);
-        
-        while !queue.is_empty() {
-            let u = *queue.iter().min_by_key(|pos| dist.get(&pos).unwrap_or(&u32::MAX)).unwrap();
-            queue.remove(&u);
-            let neighbours = u.neighbours();
-            for v in neighbours.iter().filter(|p| queue.contains(p)) {
-                let alt = dist.get(&u).unwrap() + u.distance(v);
-                if alt < *dist.get(&v).unwrap_or(&u32::MAX) {
-                    dist.insert(*v, alt);
-                    prev.insert(*v, u);
-                }
-            }
-        }
-        
-        let mut path = vec![to];
-        let mut last = prev.get(&to).unwrap();
-        while *last != from {
-            path.insert(0, *last);
-            last = prev.get(last).unwrap();
-        }
-        (*dist.get(&to).unwrap(), path)
-    }
-}
-
-impl std::fmt::Display for Kind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        write!(f, "{}", self.to_char())
-    }
-}
-
-impl Kind {
-    fn from_char(c: char) -> Kind {
-        match c {
-            'A' => Kind::Amber,
-            'B' => Kind::Bronze,
-            'C' => Kind::Copper,
-            'D' => Kind::Desert,
-            _ => panic!("No room for {}", c)
-        }
-    }
-
-    fn to_char(self) -> char {
-        match self {
-            Kind::Amber => 'A',
-            Kind::Bronze => 'B',
-            Kind::Copper => 'C',
-            Kind::Desert => 'D',
-        }
-    }
-
-    fn cost_multiplier(self) -> u32 {
-        match self {
-            Kind::Amber => 1,
-            Kind::Bronze => 10,
-            Kind::Copper => 100,
-            Kind::Desert => 1000,
+        for row in self.cells.chunks(self.width) {
+            writeln!(f, "{}", String::from_utf8_lossy(row).trim_end())?;
         }
+        Ok(())
     }
 }
 
 impl Ord for State {
     fn cmp(&self, other: &Self) -> Ordering {
         other.energy.cmp(&self.energy)
-            .then_with(|| self.positions.cmp(&other.positions))
+            .then_with(|| self.cells.cmp(&other.cells))
     }
 }
 
@@ -293,179 +67,241 @@ impl PartialOrd for State {
     }
 }
 
-impl State {
-    fn is_final(&self) -> bool {
-        use Position::*;
-        use Kind::*;
-        match self.positions {
-            [
-                Room(Amber,_),Room(Amber,_),
-                Room(Bronze,_),Room(Bronze,_),
-                Room(Copper,_),Room(Copper,_),
-                Room(Desert,_),Room(Desert,_)
-            ] => true,
-            _ => false
+fn cost_multiplier(kind: u8) -> u64 {
+    10u64.pow((kind - b'A') as u32)
+}
+
+impl Burrow {
+    fn parse(lines: &[String]) -> (Burrow, State) {
+        let width = lines.iter().map(String::len).max().unwrap_or(0);
+        let mut cells = vec![b' '; width * lines.len()];
+        for (y, line) in lines.iter().enumerate() {
+            cells[y * width..y * width + line.len()].copy_from_slice(line.as_bytes());
+        }
+        let is_open = |y: usize, x: usize| y < lines.len() && cells[y * width + x] != b'#' && cells[y * width + x] != b' ';
+
+        let hall_row = (0..lines.len()).find(|&y| (0..width).any(|x| is_open(y, x))).expect("no hallway");
+        let hall = (0..width).filter(|&x| is_open(hall_row, x)).map(|x| hall_row * width + x).collect::<Vec<_>>();
+        let mut rooms = vec![];
+        for x in (0..width).filter(|&x| is_open(hall_row, x) && is_open(hall_row + 1, x)) {
+            let cells = (hall_row + 1..).take_while(|&y| is_open(y, x)).map(|y| y * width + x).collect();
+            let kind = b'A' + rooms.len() as u8;
+            rooms.push(Room{kind, door: hall_row * width + x, cells});
+        }
+        assert!(!rooms.is_empty(), "burrow has no rooms");
+        assert!(rooms.len() <= 10, "only up to 10 rooms are supported");
+
+        for &cell in hall.iter().chain(rooms.iter().flat_map(|r| r.cells.iter())) {
+            let c = cells[cell];
+            assert!(c == EMPTY || (b'A'..b'A' + rooms.len() as u8).contains(&c), "Unexpected amphipod {}", c as char);
+        }
+        for room in rooms.iter() {
+            let count = cells.iter().filter(|&&c| c == room.kind).count();
+            assert_eq!(count, room.cells.len(), "Expected {} amphipods of kind {}", room.cells.len(), room.kind as char);
         }
+        (Burrow{hall, rooms}, State{energy: 0, width, cells})
     }
 
-    fn find_in(&self, pos: Position) -> Option<Amphipod> {
-        self.positions.iter().position(|&p| p == pos).map(|idx| AMPHIPODS[idx])
+    fn is_door(&self, cell: usize) -> bool {
+        self.rooms.iter().any(|r| r.door == cell)
     }
 
-    fn kind_in(&self, pos: Position) -> Option<Kind> {
-        self.find_in(pos).map(|a| a.kind)
+    fn room_for(&self, kind: u8) -> &Room {
+        &self.rooms[(kind - b'A') as usize]
     }
 
-    fn is_path_clear(&self, path: &Vec<Position>) -> bool {
-        for &pos in path {
-            if self.find_in(pos).is_some() {
-                return false;
-            }
+    // hallway steps between two hallway cells, if nothing is in the way
+    fn hall_distance(&self, state: &State, from: usize, to: usize) -> Option<u64> {
+        let range = if from < to { from + 1..=to } else { to..=from - 1 };
+        if range.clone().any(|cell| state.cells[cell] != EMPTY) {
+            return None;
         }
-        return true;
+        Some(range.count() as u64)
     }
+}
 
-    fn room_has_no_strangers(&self, kind: Kind) -> bool {
-        let p1 = Position::Room(kind, Side::Top);
-        let p2 = Position::Room(kind, Side::Bottom);
-        self.kind_in(p1).unwrap_or(kind) == kind && 
-        self.kind_in(p2).unwrap_or(kind) == kind
+impl State {
+    fn is_final(&self, burrow: &Burrow) -> bool {
+        burrow.rooms.iter().all(|room| room.cells.iter().all(|&c| self.cells[c] == room.kind))
     }
 
-    fn room_is_good(&self, kind: Kind) -> bool {
-        let p1 = Position::Room(kind, Side::Top);
-        let p2 = Position::Room(kind, Side::Bottom);
-        self.kind_in(p1) == Some(kind) && 
-        self.kind_in(p2) == Some(kind)
+    fn room_has_no_strangers(&self, room: &Room) -> bool {
+        room.cells.iter().all(|&c| self.cells[c] == EMPTY || self.cells[c] == room.kind)
     }
 
-    fn would_move_to(&self, who: Amphipod, to: Position) -> bool {
-        let idx = who.index;
-        let from = self.positions[idx];
-        if from == to {
-            // u silly
-            return false;
-        } else if let Position::Room(room_kind,_) = to {
-            // to their destination room
-            // as long as it contains no other amphipods
-            return room_kind == who.kind && self.room_has_no_strangers(room_kind);
-        } else if let (Position::Room(_,_),Position::Hall(_)) = (from,to) {
-            // from room to hall
-           return true;
+    // deepest free cell in the room and the steps from the door to it
+    fn room_entry(&self, room: &Room) -> Option<(usize, u64)> {
+        if !self.room_has_no_strangers(room) {
+            return None;
         }
-        return false;
+        room.cells.iter().enumerate().rev()
+            .find(|(_, &c)| self.cells[c] == EMPTY)
+            .map(|(depth, &c)| (c, depth as u64 + 1))
     }
 
-    fn can_move_to(&self, who: Amphipod, to: Position, paths: &PathCache) -> (u32, bool) {
-        let idx = who.index;
-        let from = self.positions[idx];
-        if from == to || !self.would_move_to(who, to) {
-            return (u32::MAX, false);
+    // topmost amphipod in the room and the steps from it to the door
+    fn room_exit(&self, room: &Room) -> Option<(usize, u64)> {
+        if self.room_has_no_strangers(room) {
+            return None;
         }
-        let (cost, path) = from.path(to, paths);
-        return (cost, self.is_path_clear(&path));
+        room.cells.iter().enumerate()
+            .find(|(_, &c)| self.cells[c] != EMPTY)
+            .map(|(depth, &c)| (c, depth as u64 + 1))
     }
 
-    fn do_move(&self, who: Amphipod, to: Position, paths: &PathCache) -> State {
-        let mut state = *self;
-        let idx = who.index;
-        let from = self.positions[idx];
-        let (cost, _) = from.path(to, paths);
-        state.positions[idx] = to;
-        state.energy += who.kind.cost_multiplier() * cost;
-        state
+    // None if the energy doesn't fit
+    fn do_move(&self, mv: &Move) -> Option<State> {
+        let mut state = self.clone();
+        let kind = state.cells[mv.from];
+        state.cells[mv.from] = EMPTY;
+        state.cells[mv.to] = kind;
+        state.energy = cost_multiplier(kind).checked_mul(mv.cost)
+            .and_then(|cost| state.energy.checked_add(cost))?;
+        Some(state)
     }
 
-    fn available_moves(&self, paths: &PathCache) -> Vec<(Amphipod, Position, u32)> {
+    fn available_moves(&self, burrow: &Burrow) -> Vec<Move> {
         let mut moves = vec![];
-        for a in AMPHIPODS {
-            for p in POSITIONS {
-                if let (cost, true) = self.can_move_to(a, p, paths) {
-                    moves.push((a,p,cost));
+        // from the hallway into their destination room
+        for &from in burrow.hall.iter().filter(|&&c| self.cells[c] != EMPTY) {
+            let room = burrow.room_for(self.cells[from]);
+            if let (Some((to, down)), Some(steps)) = (self.room_entry(room), burrow.hall_distance(self, from, room.door)) {
+                moves.push(Move{from, to, cost: steps + down});
+            }
+        }
+        // out of a room, straight into the destination room or stopping in the hallway
+        for room in burrow.rooms.iter() {
+            let (from, up) = match self.room_exit(room) {
+                Some(exit) => exit,
+                None => continue,
+            };
+            let target = burrow.room_for(self.cells[from]);
+            if let (Some((to, down)), Some(steps)) = (self.room_entry(target), burrow.hall_distance(self, room.door, target.door)) {
+                moves.push(Move{from, to, cost: up + steps + down});
+                continue;
+            }
+            for &to in burrow.hall.iter().filter(|&&c| !burrow.is_door(c)) {
+                if let Some(steps) = burrow.hall_distance(self, room.door, to) {
+                    moves.push(Move{from, to, cost: up + steps});
                 }
             }
         }
-        moves.sort_by_key(|(_,_,cost)| *cost);
         moves
     }
 }
 
-fn read_input() -> State {
-    let stdin = io::stdin();
-    let mut kinds: Vec<Kind> = vec![];
-    for line in stdin.lock().lines() {
-        line.unwrap().chars().filter(char::is_ascii_alphabetic).for_each(|c| kinds.push(Kind::from_char(c)));
-    }
-    assert_eq!(8, kinds.len(), "Expected input with 8 letters");
-    let mut positions = [Position::default(); 8];
-    let mut next_a = vec![0usize,1usize];
-    let mut next_b = vec![2usize,3usize];
-    let mut next_c = vec![4usize,5usize];
-    let mut next_d = vec![6usize,7usize];
-    let mut room_positions = [
-        Position::Room(Kind::Amber, Side::Top),
-        Position::Room(Kind::Bronze, Side::Top),
-        Position::Room(Kind::Copper, Side::Top),
-        Position::Room(Kind::Desert, Side::Top),
-        Position::Room(Kind::Amber, Side::Bottom),
-        Position::Room(Kind::Bronze, Side::Bottom),
-        Position::Room(Kind::Copper, Side::Bottom),
-        Position::Room(Kind::Desert, Side::Bottom),
-    ].iter();
-    
-    for c in kinds {
-        let pos = *room_positions.next().unwrap();
-        let nexts = match c {
-            Kind::Amber => &mut next_a,
-            Kind::Bronze => &mut next_b,
-            Kind::Copper => &mut next_c,
-            Kind::Desert => &mut next_d,
-        };
-        positions[nexts.remove(0)] = pos;
+fn read_input(unfold: bool) -> (Burrow, State) {
+    let mut lines = io::stdin().lock().lines()
+        .map(Result::unwrap)
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>();
+    if unfold {
+        unfold_diagram(&mut lines);
     }
-    return State{energy: 0, positions: positions }
+    Burrow::parse(&lines)
+}
+
+// part 2: the folded-up section of the diagram
+fn unfold_diagram(lines: &mut Vec<String>) {
+    lines.insert(3, "  #D#C#B#A#".to_string());
+    lines.insert(4, "  #D#B#A#C#".to_string());
 }
 
-fn find_lowest_cost(start: &State) -> Vec<State> {
+// returns the states along the cheapest path and the number of states explored
+fn find_lowest_cost(burrow: &Burrow, start: &State) -> (Vec<State>, usize) {
     let mut q = BinaryHeap::new();
-    let paths = precompute_paths();
-    let mut prev = HashMap::new();
-    let mut lowest = State{energy:u32::MAX, positions: Default::default()};
-    q.push(*start);
-    while !q.is_empty() {
-        let start = q.pop().unwrap();
-        for (a,p,_) in start.available_moves(&paths) {
-            let state = start.do_move(a,p,&paths);
-            if state.energy > lowest.energy {
-                continue;
+    let mut prev: HashMap<Vec<u8>, (u64, Option<State>)> = HashMap::new();
+    let mut explored = 0;
+    prev.insert(start.cells.clone(), (0, None));
+    q.push(start.clone());
+    while let Some(state) = q.pop() {
+        if prev.get(&state.cells).map(|(e,_)| *e) != Some(state.energy) {
+            // already reached with less energy
+            continue;
+        }
+        explored += 1;
+        if state.is_final(burrow) {
+            let mut states = vec![state];
+            while let Some((_, Some(before))) = prev.get(&states[0].cells) {
+                states.insert(0, before.clone());
             }
-            if state.is_final() {
-                lowest = state;
-                println!("Found a final with {}", state.energy);
-                prev.insert(state.positions, (state.energy, start.positions));
-            } else if prev.get(&state.positions).map(|(e,_)| *e).unwrap_or(u32::MAX) > state.energy {
-                prev.insert(state.positions, (state.energy, start.positions));
-                q.push(state);
+            return (states, explored);
+        }
+        for mv in state.available_moves(burrow) {
+            let next = match state.do_move(&mv) {
+                Some(next) => next,
+                None => continue, // costs more than any energy we can count
+            };
+            if prev.get(&next.cells).map(|(e,_)| *e).unwrap_or(u64::MAX) > next.energy {
+                prev.insert(next.cells.clone(), (next.energy, Some(state.clone())));
+                q.push(next);
             }
         }
     }
-    
-    let mut states = vec![lowest];
-    while prev.contains_key(&states[0].positions) {
-        let (energy, positions) = *prev.get(&states[0].positions).unwrap();
-        let state = State{energy: energy, positions: positions};
-        states[0].energy = energy;
-        states.insert(0, state);
-    }
-    states[0].energy = 0;
-    states
+    (vec![], explored)
 }
 
 fn main() {
-    let state = read_input();
+    let unfold = std::env::args().any(|arg| arg == "--unfold");
+    let (burrow, state) = read_input(unfold);
     println!("Start:\n{}", state);
-    
-    for state in find_lowest_cost(&state) {
-        println!("{}Energy: {}", state, state.energy);
+
+    let (states, explored) = find_lowest_cost(&burrow, &state);
+    if states.is_empty() {
+        println!("No solution after exploring {} states", explored);
+        return;
+    }
+    for pair in states.windows(2) {
+        println!("{}Cost: {}, energy: {}", pair[1], pair[1].energy - pair[0].energy, pair[1].energy);
+    }
+    println!("Lowest energy: {} ({} moves, {} states explored)", states.last().unwrap().energy, states.len() - 1, explored);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(diagram: &str) -> Vec<String> {
+        diagram.lines().map(str::to_string).collect()
+    }
+
+    fn lowest_energy(lines: &[String]) -> Option<u64> {
+        let (burrow, state) = Burrow::parse(lines);
+        find_lowest_cost(&burrow, &state).0.last().map(|s| s.energy)
+    }
+
+    const EXAMPLE: &str = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    #[test]
+    fn example() {
+        let mut diagram = lines(EXAMPLE);
+        assert_eq!(lowest_energy(&diagram), Some(12521));
+        unfold_diagram(&mut diagram);
+        assert_eq!(lowest_energy(&diagram), Some(44169));
+    }
+
+    #[test]
+    fn three_rooms() {
+        // A steps aside so B can go straight home
+        let diagram = lines("###########
+#.........#
+###B#A#C###
+  #A#B#C#
+  #######");
+        assert_eq!(lowest_energy(&diagram), Some(2 + 40 + 4));
+    }
+
+    #[test]
+    fn ten_rooms() {
+        // more energy than fits in 32 bits
+        let diagram = lines("#########################
+#.......................#
+###A#B#C#D#E#F#G#H#J#I###
+  #####################");
+        assert_eq!(lowest_energy(&diagram), Some(200_000_000 + 4_000_000_000 + 400_000_000));
     }
 }
@@ -0,0 +1,115 @@
+//! Square matrices over any integer type, for puzzles where one step is a linear
+//! map on some counts and many steps are a matrix power.
+
+use num_traits::{CheckedAdd, CheckedMul, One, Zero};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+    size: usize,
+    cells: Vec<T>, // row-major
+}
+
+impl<T: Clone + Zero + One + CheckedAdd + CheckedMul> Matrix<T> {
+    pub fn zero(size: usize) -> Matrix<T> {
+        Matrix{size, cells: vec![T::zero(); size*size]}
+    }
+
+    pub fn identity(size: usize) -> Matrix<T> {
+        let mut m = Self::zero(size);
+        for i in 0..size {
+            m.cells[i*size+i] = T::one();
+        }
+        m
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> &T {
+        &self.cells[row*self.size+col]
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> &mut T {
+        &mut self.cells[row*self.size+col]
+    }
+
+    /// Matrix product, `None` on overflow.
+    pub fn checked_mul(&self, other: &Matrix<T>) -> Option<Matrix<T>> {
+        let size = self.size;
+        let mut result = Self::zero(size);
+        for row in 0..size {
+            for k in 0..size {
+                let a = self.get(row, k);
+                if a.is_zero() {
+                    continue;
+                }
+                for col in 0..size {
+                    let product = a.checked_mul(other.get(k, col))?;
+                    let cell = result.get_mut(row, col);
+                    *cell = cell.checked_add(&product)?;
+                }
+            }
+        }
+        Some(result)
+    }
+
+    /// Exponentiation by squaring, `None` on overflow.
+    pub fn checked_pow(&self, mut exp: u64) -> Option<Matrix<T>> {
+        let mut result = Self::identity(self.size);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.checked_mul(&base)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.checked_mul(&base)?;
+            }
+        }
+        Some(result)
+    }
+
+    /// Product with a column vector, `None` on overflow.
+    pub fn checked_apply(&self, vector: &[T]) -> Option<Vec<T>> {
+        (0..self.size).map(|row| {
+            vector.iter().enumerate().filter(|(_, v)| !v.is_zero())
+                .try_fold(T::zero(), |acc, (col, v)| acc.checked_add(&self.get(row, col).checked_mul(v)?))
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+
+    // Fibonacci numbers: [[1,1],[1,0]]^n = [[F(n+1),F(n)],[F(n),F(n-1)]]
+    fn fibonacci<T: Clone + Zero + One + CheckedAdd + CheckedMul>() -> Matrix<T> {
+        let mut m = Matrix::identity(2);
+        *m.get_mut(0, 1) = T::one();
+        *m.get_mut(1, 0) = T::one();
+        *m.get_mut(1, 1) = T::zero();
+        m
+    }
+
+    #[test]
+    fn pow() {
+        let m = fibonacci::<u64>();
+        assert_eq!(m.checked_pow(0), Some(Matrix::identity(2)));
+        let mut product = Matrix::identity(2);
+        for n in 1..=90 {
+            product = product.checked_mul(&m).unwrap();
+            assert_eq!(m.checked_pow(n).as_ref(), Some(&product), "power {}", n);
+        }
+        assert_eq!(*m.checked_pow(90).unwrap().get(0, 1), 2880067194370816120);
+        assert_eq!(m.checked_apply(&[1, 0]), Some(vec![1, 1]));
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(fibonacci::<u64>().checked_pow(100), None);
+        let big = fibonacci::<BigUint>().checked_pow(100).unwrap();
+        assert_eq!(big.get(0, 1).to_string(), "354224848179261915075");
+    }
+}
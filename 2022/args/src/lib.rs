@@ -0,0 +1,90 @@
+//! Command line flags for days whose parameters differ between the example and
+//! the real input: `--example` picks the example profile, and any other flags
+//! override it. Bad arguments print the usage and exit instead of panicking.
+
+use std::str::FromStr;
+
+pub struct Args {
+    args: std::vec::IntoIter<String>,
+    usage: &'static str,
+    example: bool,
+}
+
+impl Args {
+    /// The program's arguments, `usage` lists them for error messages.
+    pub fn new(usage: &'static str) -> Args {
+        Args::from_vec(std::env::args().skip(1).collect(), usage)
+    }
+
+    pub fn from_vec(args: Vec<String>, usage: &'static str) -> Args {
+        let example = args.iter().any(|arg| arg == "--example");
+        Args {
+            args: args.into_iter(),
+            usage,
+            example,
+        }
+    }
+
+    /// Whether `--example` was given anywhere, so the profile can be picked
+    /// before applying overrides.
+    pub fn example(&self) -> bool {
+        self.example
+    }
+
+    /// The next flag, skipping `--example`.
+    pub fn next_flag(&mut self) -> Option<String> {
+        self.args.by_ref().find(|arg| arg != "--example")
+    }
+
+    /// The argument after `flag`.
+    pub fn value<T: FromStr>(&mut self, flag: &str) -> T {
+        match self.args.next().map(|v| v.parse()) {
+            Some(Ok(value)) => value,
+            _ => self.fail(&format!("{} needs a value", flag)),
+        }
+    }
+
+    /// The argument after `flag`, as `count` values separated by commas.
+    pub fn values<T: FromStr>(&mut self, flag: &str, count: usize) -> Vec<T> {
+        let values = self.args.next().map(|v| {
+            v.split(',')
+                .map(|n| n.parse())
+                .collect::<Result<Vec<_>, _>>()
+        });
+        match values {
+            Some(Ok(values)) if values.len() == count => values,
+            _ => self.fail(&format!("{} needs {} values", flag, count)),
+        }
+    }
+
+    pub fn unknown(&self, flag: &str) -> ! {
+        self.fail(&format!("Unknown argument {}", flag))
+    }
+
+    pub fn fail(&self, message: &str) -> ! {
+        eprintln!("{}", message);
+        eprintln!("usage: {}", self.usage);
+        std::process::exit(2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Args {
+        Args::from_vec(args.iter().map(|s| s.to_string()).collect(), "")
+    }
+
+    #[test]
+    fn test_flags() {
+        let mut a = args(&["--row", "10", "--example", "--window", "0,-1,20,21"]);
+        assert!(a.example());
+        assert_eq!(a.next_flag().as_deref(), Some("--row"));
+        assert_eq!(a.value::<i32>("--row"), 10);
+        assert_eq!(a.next_flag().as_deref(), Some("--window"));
+        assert_eq!(a.values::<i32>("--window", 4), vec![0, -1, 20, 21]);
+        assert_eq!(a.next_flag(), None);
+        assert!(!args(&["--row", "10"]).example());
+    }
+}
@@ -0,0 +1,210 @@
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize, // stack label
+    pub to: usize,   // stack label
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Model {
+    /// Moves crates one at a time.
+    CrateMover9000,
+    /// Moves crates all at once.
+    CrateMover9001,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cargo {
+    labels: Vec<usize>,
+    stacks: Vec<Vec<char>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MoveError {
+    NoSuchStack(usize),
+    Underflow {
+        stack: usize,
+        crates: usize,
+        wanted: usize,
+    },
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::NoSuchStack(label) => write!(f, "there is no stack {}", label),
+            MoveError::Underflow {
+                stack,
+                crates,
+                wanted,
+            } => write!(
+                f,
+                "stack {} has {} crates, can't move {}",
+                stack, crates, wanted
+            ),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    // "move 1 from 2 to 1"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        let number = |word: &str| {
+            word.parse::<usize>()
+                .map_err(|_| format!("Invalid move: {}", s))
+        };
+        match words[..] {
+            ["move", count, "from", from, "to", to] => Ok(Move {
+                count: number(count)?,
+                from: number(from)?,
+                to: number(to)?,
+            }),
+            _ => Err(format!("Invalid move: {}", s)),
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+impl FromStr for Cargo {
+    type Err = String;
+
+    /// Reads the drawing; each crate belongs to the label nearest to it in the last row,
+    /// so any number of stacks and label widths work.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s
+            .lines()
+            .filter(|ln| !ln.trim().is_empty())
+            .collect::<Vec<_>>();
+        let (header, rows) = lines.split_last().ok_or("Empty drawing")?;
+        let mut labels = vec![];
+        let mut centers = vec![];
+        let mut col = 0;
+        for word in header.split(' ') {
+            if !word.is_empty() {
+                labels.push(
+                    word.parse::<usize>()
+                        .map_err(|_| format!("Invalid stack label {}", word))?,
+                );
+                // twice the center, to stay in integers
+                centers.push(2 * col + word.len() - 1);
+            }
+            col += word.len() + 1;
+        }
+        if labels.is_empty() {
+            return Err("No stacks".to_string());
+        }
+        let mut stacks = vec![vec![]; labels.len()];
+        for row in rows.iter().rev() {
+            let chars = row.chars().collect::<Vec<_>>();
+            for (pos, c) in chars.iter().enumerate() {
+                if pos == 0
+                    || pos + 1 >= chars.len()
+                    || chars[pos - 1] != '['
+                    || chars[pos + 1] != ']'
+                {
+                    continue;
+                }
+                let stack = (0..centers.len())
+                    .min_by_key(|&i| centers[i].abs_diff(2 * pos))
+                    .unwrap();
+                stacks[stack].push(*c);
+            }
+        }
+        Ok(Cargo { labels, stacks })
+    }
+}
+
+impl Display for Cargo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (label, stack) in self.labels.iter().zip(self.stacks.iter()) {
+            writeln!(f, "{}: {}", label, stack.iter().collect::<String>())?
+        }
+        Ok(())
+    }
+}
+
+impl Cargo {
+    fn index(&self, label: usize) -> Result<usize, MoveError> {
+        self.labels
+            .iter()
+            .position(|&l| l == label)
+            .ok_or(MoveError::NoSuchStack(label))
+    }
+
+    /// Moves crates in place, or leaves everything untouched on error.
+    pub fn apply(&mut self, mv: &Move, model: Model) -> Result<(), MoveError> {
+        let (from, to) = (self.index(mv.from)?, self.index(mv.to)?);
+        let crates = self.stacks[from].len();
+        if mv.count > crates {
+            return Err(MoveError::Underflow {
+                stack: mv.from,
+                crates,
+                wanted: mv.count,
+            });
+        }
+        if from == to {
+            // picking up and putting back on the same stack
+            return Ok(());
+        }
+        let mut moving = self.stacks[from].split_off(crates - mv.count);
+        if model == Model::CrateMover9000 {
+            moving.reverse();
+        }
+        self.stacks[to].append(&mut moving);
+        Ok(())
+    }
+
+    pub fn tops(&self) -> String {
+        self.stacks.iter().filter_map(|s| s.last()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_many_stacks() {
+        let drawing = "
+                                            [K]
+[A] [B] [C] [D] [E] [F] [G] [H] [I] [J]     [L]
+ 1   2   3   4   5   6   7   8   9   10  11  12 ";
+        let mut cargo: Cargo = drawing.parse().unwrap();
+        assert_eq!(cargo.tops(), "ABCDEFGHIJK");
+        cargo
+            .apply(
+                &"move 2 from 12 to 11".parse().unwrap(),
+                Model::CrateMover9000,
+            )
+            .unwrap();
+        assert_eq!(cargo.tops(), "ABCDEFGHIJL");
+        assert_eq!(
+            cargo.apply(
+                &"move 3 from 11 to 1".parse().unwrap(),
+                Model::CrateMover9001
+            ),
+            Err(MoveError::Underflow {
+                stack: 11,
+                crates: 2,
+                wanted: 3
+            })
+        );
+        assert_eq!(
+            cargo.apply(
+                &"move 1 from 13 to 1".parse().unwrap(),
+                Model::CrateMover9001
+            ),
+            Err(MoveError::NoSuchStack(13))
+        );
+    }
+}
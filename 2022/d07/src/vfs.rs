@@ -0,0 +1,409 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    ops::RangeBounds,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    Directory(BTreeMap<String, Entry>),
+    File(usize),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnknownCommand(String),
+    /// Listing output when the last command wasn't `ls`.
+    UnexpectedOutput(String),
+    BadListing(String),
+    NotADirectory(String),
+    /// `cd` into a directory its parent's listing didn't show.
+    NoSuchDirectory(String),
+    NoParent,
+    /// A listing disagrees with an earlier one.
+    Conflict(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Error {
+    pub line: usize,
+    pub kind: ErrorKind,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ErrorKind::UnknownCommand(cmd) => write!(f, "unknown command {}", cmd),
+            ErrorKind::UnexpectedOutput(line) => write!(f, "output without ls: {}", line),
+            ErrorKind::BadListing(line) => write!(f, "can't read listing {}", line),
+            ErrorKind::NotADirectory(path) => write!(f, "{} is not a directory", path),
+            ErrorKind::NoSuchDirectory(path) => write!(f, "no directory {}", path),
+            ErrorKind::NoParent => write!(f, "/ has no parent"),
+            ErrorKind::Conflict(path) => write!(f, "{} changed since it was last listed", path),
+        }
+    }
+}
+
+/// Filesystem rebuilt from a shell session.
+#[derive(Debug)]
+pub struct Filesystem {
+    root: Entry,
+    cwd: Vec<String>,
+    listing: Option<(usize, BTreeSet<String>)>, // line of the current `ls` and the names it listed
+    listed: BTreeSet<Vec<String>>,              // directories listed before
+    lines: usize,
+}
+
+fn path_string(path: &[String]) -> String {
+    format!("/{}", path.join("/"))
+}
+
+impl Entry {
+    pub fn total_size(&self) -> usize {
+        match self {
+            Entry::Directory(entries) => entries.values().map(Entry::total_size).sum(),
+            Entry::File(size) => *size,
+        }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        matches!(self, Entry::Directory(_))
+    }
+
+    fn dir_mut(&mut self) -> Option<&mut BTreeMap<String, Entry>> {
+        match self {
+            Entry::Directory(entries) => Some(entries),
+            Entry::File(_) => None,
+        }
+    }
+
+    // calls `visit` with the path, entry and total size of this and everything below, children first
+    fn walk(
+        &self,
+        path: &mut Vec<String>,
+        visit: &mut dyn FnMut(&[String], &Entry, usize),
+    ) -> usize {
+        let size = match self {
+            Entry::Directory(entries) => entries
+                .iter()
+                .map(|(name, entry)| {
+                    path.push(name.clone());
+                    let size = entry.walk(path, visit);
+                    path.pop();
+                    size
+                })
+                .sum(),
+            Entry::File(size) => *size,
+        };
+        visit(path, self, size);
+        size
+    }
+
+    fn fmt_by_size(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        name: &str,
+        indent: usize,
+    ) -> std::fmt::Result {
+        match self {
+            Entry::Directory(entries) => {
+                writeln!(
+                    f,
+                    "{:>width$}{} ({})",
+                    "",
+                    name,
+                    self.total_size(),
+                    width = indent
+                )?;
+                let mut sorted = entries
+                    .iter()
+                    .map(|(name, e)| (e.total_size(), name, e))
+                    .collect::<Vec<_>>();
+                sorted.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+                for (_, name, entry) in sorted {
+                    entry.fmt_by_size(f, name, indent + 2)?;
+                }
+                Ok(())
+            }
+            Entry::File(size) => writeln!(f, "{:>width$}{} {}", "", name, size, width = indent),
+        }
+    }
+}
+
+impl Default for Filesystem {
+    fn default() -> Self {
+        Filesystem {
+            root: Entry::Directory(BTreeMap::new()),
+            cwd: vec![],
+            listing: None,
+            listed: BTreeSet::new(),
+            lines: 0,
+        }
+    }
+}
+
+impl Filesystem {
+    pub fn replay<I: Iterator<Item = String>>(lines: I) -> Result<Filesystem, Error> {
+        let mut fs = Filesystem::default();
+        for line in lines {
+            fs.run(&line)?;
+        }
+        fs.finish()?;
+        Ok(fs)
+    }
+
+    pub fn root(&self) -> &Entry {
+        &self.root
+    }
+
+    /// Processes one line of the session, a command or the output of `ls`.
+    /// Blank lines are skipped, but still counted in error line numbers.
+    pub fn run(&mut self, line: &str) -> Result<(), Error> {
+        self.lines += 1;
+        let line_number = self.lines;
+        let error = |kind| Error {
+            line: line_number,
+            kind,
+        };
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words[..] {
+            [] => Ok(()),
+            ["$", "cd", path] => {
+                self.finish()?;
+                self.cd(path).map_err(error)
+            }
+            ["$", "ls"] => {
+                self.finish()?;
+                self.listing = Some((line_number, BTreeSet::new()));
+                Ok(())
+            }
+            ["$", ..] => Err(error(ErrorKind::UnknownCommand(line.to_string()))),
+            _ if self.listing.is_none() => {
+                Err(error(ErrorKind::UnexpectedOutput(line.to_string())))
+            }
+            ["dir", name] => self
+                .add(name, Entry::Directory(BTreeMap::new()))
+                .map_err(error),
+            [size, name] => match size.parse() {
+                Ok(size) => self.add(name, Entry::File(size)).map_err(error),
+                Err(_) => Err(error(ErrorKind::BadListing(line.to_string()))),
+            },
+            _ => Err(error(ErrorKind::BadListing(line.to_string()))),
+        }
+    }
+
+    /// Ends the current listing, which must show everything an earlier listing
+    /// of the same directory did.
+    pub fn finish(&mut self) -> Result<(), Error> {
+        let (line, seen) = match self.listing.take() {
+            Some(listing) => listing,
+            None => return Ok(()),
+        };
+        if self.listed.insert(self.cwd.clone()) {
+            return Ok(());
+        }
+        let cwd = self.cwd.clone();
+        let entries = self.dir_mut(&cwd).map_err(|kind| Error { line, kind })?;
+        match entries.keys().find(|name| !seen.contains(*name)) {
+            Some(name) => {
+                let mut path = cwd;
+                path.push(name.clone());
+                Err(Error {
+                    line,
+                    kind: ErrorKind::Conflict(path_string(&path)),
+                })
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn cd(&mut self, path: &str) -> Result<(), ErrorKind> {
+        let mut cwd = if path.starts_with('/') {
+            vec![]
+        } else {
+            self.cwd.clone()
+        };
+        for part in path.split('/').filter(|p| !p.is_empty() && *p != ".") {
+            if part == ".." {
+                cwd.pop().ok_or(ErrorKind::NoParent)?;
+            } else {
+                cwd.push(part.to_string());
+                let (name, parent) = cwd.split_last().unwrap();
+                match self.dir_mut(parent)?.get(name) {
+                    Some(entry) if entry.is_dir() => (),
+                    Some(_) => return Err(ErrorKind::NotADirectory(path_string(&cwd))),
+                    None => return Err(ErrorKind::NoSuchDirectory(path_string(&cwd))),
+                }
+            }
+        }
+        self.cwd = cwd;
+        Ok(())
+    }
+
+    fn dir_mut(&mut self, path: &[String]) -> Result<&mut BTreeMap<String, Entry>, ErrorKind> {
+        let mut entry = &mut self.root;
+        for (depth, name) in path.iter().enumerate() {
+            let not_dir = || ErrorKind::NotADirectory(path_string(&path[..=depth]));
+            entry = entry
+                .dir_mut()
+                .ok_or_else(not_dir)?
+                .get_mut(name)
+                .ok_or_else(not_dir)?;
+        }
+        entry
+            .dir_mut()
+            .ok_or_else(|| ErrorKind::NotADirectory(path_string(path)))
+    }
+
+    fn add(&mut self, name: &str, new: Entry) -> Result<(), ErrorKind> {
+        let mut path = self.cwd.clone();
+        path.push(name.to_string());
+        if let Some((_, seen)) = self.listing.as_mut() {
+            seen.insert(name.to_string());
+        }
+        let listed = self.listed.contains(&self.cwd);
+        let entries = self.dir_mut(&self.cwd.clone())?;
+        match entries.get(name) {
+            // a new entry in a directory listed before
+            None if listed => Err(ErrorKind::Conflict(path_string(&path))),
+            None => {
+                entries.insert(name.to_string(), new);
+                Ok(())
+            }
+            // listed again, keep what's known about the directory's contents
+            Some(old) if old.is_dir() && new.is_dir() => Ok(()),
+            Some(old) if *old == new => Ok(()),
+            Some(_) => Err(ErrorKind::Conflict(path_string(&path))),
+        }
+    }
+
+    /// Total size of every directory, like `du`.
+    pub fn du(&self) -> Vec<(String, usize)> {
+        self.find(true, ..)
+    }
+
+    /// Paths and sizes of entries with a total size in `size`, like `find -size`.
+    pub fn find<R: RangeBounds<usize>>(&self, dirs_only: bool, size: R) -> Vec<(String, usize)> {
+        let mut found = vec![];
+        self.root.walk(&mut vec![], &mut |path, entry, total| {
+            if (entry.is_dir() || !dirs_only) && size.contains(&total) {
+                found.push((path_string(path), total));
+            }
+        });
+        found.sort();
+        found
+    }
+}
+
+/// The tree with the biggest entries first.
+pub struct SortedBySize<'a>(pub &'a Filesystem);
+
+impl Display for SortedBySize<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.root.fmt_by_size(f, "/", 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay(session: &str) -> Result<Filesystem, Error> {
+        Filesystem::replay(session.lines().map(String::from))
+    }
+
+    #[test]
+    fn test_revisits() {
+        let fs = replay(
+            "$ cd /\n$ ls\ndir a\n1 x\n$ cd a\n$ ls\ndir b\n2 y\n$ cd /\n$ ls\ndir a\n1 x\n$ cd /a/b\n$ ls\n3 z\n$ cd ../..\n$ cd a\n$ ls\n2 y\ndir b",
+        )
+        .unwrap();
+        assert_eq!(
+            fs.du(),
+            vec![
+                ("/".to_string(), 6),
+                ("/a".to_string(), 5),
+                ("/a/b".to_string(), 3)
+            ]
+        );
+        assert_eq!(
+            fs.find(false, 2..=3),
+            vec![
+                ("/a/b".to_string(), 3),
+                ("/a/b/z".to_string(), 3),
+                ("/a/y".to_string(), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let kind = |session: &str| replay(session).unwrap_err().kind;
+        assert_eq!(
+            kind("$ cd /\n$ ls\n1 x\n$ ls\n2 x"),
+            ErrorKind::Conflict("/x".to_string())
+        );
+        assert_eq!(
+            kind("$ ls\n1 x\n$ cd x"),
+            ErrorKind::NotADirectory("/x".to_string())
+        );
+        assert_eq!(kind("$ cd /\n$ cd .."), ErrorKind::NoParent);
+        assert_eq!(
+            kind("$ cd /\n1 x"),
+            ErrorKind::UnexpectedOutput("1 x".to_string())
+        );
+        assert_eq!(
+            kind("$ rm -rf /"),
+            ErrorKind::UnknownCommand("$ rm -rf /".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unlisted_directory() {
+        let kind = |session: &str| replay(session).unwrap_err().kind;
+        assert_eq!(
+            kind("$ cd /\n$ ls\ndir a\n$ cd b"),
+            ErrorKind::NoSuchDirectory("/b".to_string())
+        );
+        assert_eq!(
+            kind("$ cd /\n$ cd a/b"),
+            ErrorKind::NoSuchDirectory("/a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_relisting() {
+        let kind = |session: &str| replay(session).unwrap_err().kind;
+        // omitted at the end of the session, and before the next command
+        assert_eq!(
+            kind("$ cd /\n$ ls\n1 x\ndir a\n$ ls\n1 x"),
+            ErrorKind::Conflict("/a".to_string())
+        );
+        assert_eq!(
+            kind("$ ls\n1 x\n2 y\n$ ls\n2 y\n$ ls\n1 x\n2 y"),
+            ErrorKind::Conflict("/x".to_string())
+        );
+        // added
+        assert_eq!(
+            kind("$ ls\n1 x\n$ ls\n1 x\n2 y"),
+            ErrorKind::Conflict("/y".to_string())
+        );
+        // an empty directory stays empty
+        assert_eq!(
+            kind("$ ls\ndir a\n$ cd a\n$ ls\n$ cd ..\n$ cd a\n$ ls\n1 x"),
+            ErrorKind::Conflict("/a/x".to_string())
+        );
+        assert!(replay("$ ls\ndir a\n1 x\n$ ls\n1 x\ndir a\n$ ls").is_err());
+        assert!(replay("$ ls\ndir a\n1 x\n$ ls\n1 x\ndir a").is_ok());
+    }
+
+    #[test]
+    fn test_line_numbers() {
+        let error = replay("$ cd /\n\n$ ls\n\n1 x\n\n$ ls\n\n$ cd x").unwrap_err();
+        assert_eq!(error.line, 7);
+        let error = replay("$ cd /\n\n\n$ cd a").unwrap_err();
+        assert_eq!(error.line, 4);
+        assert_eq!(error.to_string(), "line 4: no directory /a");
+    }
+}
@@ -0,0 +1,209 @@
+use std::{collections::HashMap, ops::Range, str::FromStr};
+
+#[derive(Debug, Default, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct Pos {
+    pub x: i32,
+    pub y: i32,
+}
+
+macro_rules! pos {
+    ($a:expr,$b:expr) => {{
+        Pos { x: $a, y: $b }
+    }};
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+#[derive(Debug)]
+pub struct Move {
+    pub dir: Direction,
+    pub len: i32,
+}
+
+/// Rope that remembers how many times each knot stepped onto every cell.
+#[derive(Debug)]
+pub struct Rope {
+    pub knots: Vec<Pos>,
+    visits: Vec<HashMap<Pos, usize>>,
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "U" => Ok(Direction::Up),
+            "D" => Ok(Direction::Down),
+            "L" => Ok(Direction::Left),
+            "R" => Ok(Direction::Right),
+            "UL" => Ok(Direction::UpLeft),
+            "UR" => Ok(Direction::UpRight),
+            "DL" => Ok(Direction::DownLeft),
+            "DR" => Ok(Direction::DownRight),
+            x => Err(format!("Invalid direction: '{}'", x)),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        if words.len() != 2 {
+            return Err(format!("Expected direction and length: '{}'", s));
+        }
+        Ok(Move {
+            dir: words[0].parse()?,
+            len: words[1]
+                .parse()
+                .map_err(|_| format!("Invalid length: '{}'", words[1]))?,
+        })
+    }
+}
+
+impl Direction {
+    fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+}
+
+impl Pos {
+    fn is_next_to(&self, other: &Pos) -> bool {
+        (self.x - other.x).abs() <= 1 && (self.y - other.y).abs() <= 1
+    }
+
+    fn follow(&self, other: &Pos) -> Pos {
+        assert!(!self.is_next_to(other));
+        pos!(
+            self.x + (other.x - self.x).signum(),
+            self.y + (other.y - self.y).signum()
+        )
+    }
+
+    fn step(&self, direction: &Direction) -> Pos {
+        let (dx, dy) = direction.delta();
+        pos!(self.x + dx, self.y + dy)
+    }
+}
+
+impl Rope {
+    pub fn new(size: usize) -> Rope {
+        assert!(size > 1);
+        Rope {
+            knots: vec![Pos::default(); size],
+            visits: vec![HashMap::from([(Pos::default(), 1)]); size],
+        }
+    }
+
+    pub fn apply(&mut self, mv: &Move) {
+        for _ in 0..mv.len {
+            self.step(&mv.dir);
+        }
+    }
+
+    pub fn step(&mut self, direction: &Direction) {
+        self.knots[0] = self.knots[0].step(direction);
+        *self.visits[0].entry(self.knots[0]).or_default() += 1;
+        for i in 1..self.knots.len() {
+            let prev = self.knots[i - 1];
+            if prev.is_next_to(&self.knots[i]) {
+                // the rest of the rope stays put
+                break;
+            }
+            self.knots[i] = self.knots[i].follow(&prev);
+            *self.visits[i].entry(self.knots[i]).or_default() += 1;
+        }
+    }
+
+    pub fn tail(&self) -> usize {
+        self.knots.len() - 1
+    }
+
+    /// How many times `knot` stepped onto each cell, the start counting as one.
+    pub fn visits(&self, knot: usize) -> &HashMap<Pos, usize> {
+        &self.visits[knot]
+    }
+
+    /// Cells `knot` has been on.
+    pub fn visited(&self, knot: usize) -> usize {
+        self.visits[knot].len()
+    }
+
+    /// Corners of the area any knot has been on.
+    pub fn bounds(&self) -> Range<Pos> {
+        let cells = || self.visits.iter().flat_map(HashMap::keys);
+        let start = pos!(
+            cells().map(|p| p.x).min().unwrap(),
+            cells().map(|p| p.y).min().unwrap()
+        );
+        let end = pos!(
+            cells().map(|p| p.x).max().unwrap() + 1,
+            cells().map(|p| p.y).max().unwrap() + 1
+        );
+        start..end
+    }
+
+    /// Visit counts of `knot` within `corners`, indexed as `grid[y][x]`.
+    pub fn heatmap(&self, knot: usize, corners: &Range<Pos>) -> Vec<Vec<usize>> {
+        (corners.start.y..corners.end.y)
+            .map(|y| {
+                (corners.start.x..corners.end.x)
+                    .map(|x| *self.visits[knot].get(&pos!(x, y)).unwrap_or(&0))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(moves: &str, size: usize) -> Rope {
+        let mut rope = Rope::new(size);
+        for mv in moves.lines() {
+            rope.apply(&mv.parse().unwrap());
+        }
+        rope
+    }
+
+    #[test]
+    fn test_example() {
+        let moves = "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2";
+        let rope = run(moves, 2);
+        assert_eq!(rope.visited(rope.tail()), 13);
+        assert_eq!(rope.visits(0).values().sum::<usize>(), 1 + 24);
+        assert_eq!(run(moves, 10).visited(9), 1);
+    }
+
+    #[test]
+    fn test_diagonal() {
+        let rope = run("DR 3\nUL 1", 3);
+        assert_eq!(rope.knots, vec![pos!(2, 2), pos!(2, 2), pos!(1, 1)]);
+        assert_eq!(rope.visited(1), 3);
+        let corners = rope.bounds();
+        assert_eq!(corners, pos!(0, 0)..pos!(4, 4));
+        assert_eq!(rope.heatmap(0, &corners)[2], vec![0, 0, 2, 0]);
+        assert_eq!(rope.heatmap(2, &corners)[1], vec![0, 1, 0, 0]);
+    }
+}
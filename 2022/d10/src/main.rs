@@ -1,129 +1,154 @@
+mod vm;
+
 use std::{
     fmt::Display,
-    io::{self, BufRead},
+    fs::File,
+    io::{self, BufRead, BufWriter},
     str::FromStr,
 };
 
-#[derive(Debug)]
-enum Instruction {
-    Noop,
-    AddX { value: i32 },
+use args::Args;
+use vm::{Breakpoint, Instruction, Observer, State, Stop, Vm};
+
+fn read_input() -> Vec<Instruction> {
+    io::stdin()
+        .lock()
+        .lines()
+        .map_while(Result::ok)
+        .map(|ln| Instruction::from_str(ln.as_str()).unwrap())
+        .collect()
 }
 
-impl FromStr for Instruction {
-    type Err = String;
+// part 1
+#[derive(Default)]
+struct SignalStrength {
+    total: i64,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let num_words = s.split(' ').count();
-        let mut words = s.split(' ');
-        match (words.next(), num_words) {
-            (Some(word), 1) if word.eq("noop") => Ok(Instruction::Noop),
-            (Some(word), 2) if word.eq("addx") => match words.next().unwrap().parse::<i32>() {
-                Ok(value) => Ok(Instruction::AddX { value }),
-                Err(or) => Err(format!("Invalid argument: {}", or.to_string())),
-            },
-            _ => Err(format!("Parse error: {}", s)),
+impl Observer for SignalStrength {
+    fn cycle(&mut self, state: &State) {
+        let cycle = state.cycle;
+        if cycle == 20 || (cycle > 20 && (cycle - 20).is_multiple_of(40)) {
+            self.total += cycle as i64 * state.x as i64;
         }
     }
 }
 
-#[derive(Debug)]
-struct CPU<'a> {
-    program: &'a [Instruction],
-    cycle: usize,
-    instr_cycle: usize, // cycles into current instruction
-    pc: usize,
-    x: i32,
+// part 2
+#[derive(Default)]
+struct Crt {
+    rows: Vec<Vec<bool>>,
 }
 
-impl<'a> CPU<'a> {
-    fn new(program: &[Instruction]) -> CPU {
-        CPU {
-            program,
-            cycle: 1,
-            instr_cycle: 0,
-            pc: 0,
-            x: 1,
+impl Observer for Crt {
+    fn cycle(&mut self, state: &State) {
+        let sprite = state.x - 1..=state.x + 1;
+        let position = (state.cycle - 1) as i32 % 40;
+        if position == 0 {
+            self.rows.push(vec![]);
         }
+        self.rows
+            .last_mut()
+            .unwrap()
+            .push(sprite.contains(&position));
     }
+}
 
-    fn step(&mut self) {
-        assert!(self.pc < self.program.len());
-        match self.program[self.pc] {
-            Instruction::Noop => self.next_instruction(),
-            Instruction::AddX { value } => {
-                self.instr_cycle += 1;
-                if self.instr_cycle == 2 {
-                    self.x += value;
-                    self.next_instruction();
-                }
-            }
+impl Display for Crt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows.iter() {
+            let line: String = row.iter().map(|&p| if p { '#' } else { '.' }).collect();
+            writeln!(f, "{}", line)?;
         }
-        self.cycle += 1;
-    }
-
-    fn next_instruction(&mut self) {
-        self.pc += 1;
-        self.instr_cycle = 0;
+        Ok(())
     }
+}
 
-    fn is_done(&self) -> bool {
-        self.pc == self.program.len()
-    }
+enum Trace {
+    Stdout,
+    File(String),
 }
 
-impl Display for CPU<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "c{}: PC={}.{} X={}",
-            self.cycle, self.pc, self.instr_cycle, self.x
-        )
-    }
+/// Where to trace the CPU, where to stop it, and whether to read the CRT. The
+/// example traces to stdout and has no letters on its screen.
+struct Params {
+    trace: Option<Trace>,         // where to write the trace
+    crt: bool,                    // draw the screen and read it, part 2
+    breakpoints: Vec<Breakpoint>, // where to stop and print the state
 }
 
-fn read_input() -> Vec<Instruction> {
-    io::stdin()
-        .lock()
-        .lines()
-        .filter_map(Result::ok)
-        .map(|ln| Instruction::from_str(ln.as_str()).unwrap())
-        .collect()
+impl Params {
+    const REAL: Params = Params {
+        trace: None,
+        crt: true,
+        breakpoints: vec![],
+    };
+    const EXAMPLE: Params = Params {
+        trace: Some(Trace::Stdout),
+        crt: false,
+        breakpoints: vec![],
+    };
+
+    fn from_args() -> Params {
+        let mut args = Args::new(
+            "d10 [--example] [--trace FILE|-] [--no-trace] [--crt] [--no-crt] [--break-cycle N] [--break-x N]",
+        );
+        let mut params = if args.example() {
+            Params::EXAMPLE
+        } else {
+            Params::REAL
+        };
+        while let Some(flag) = args.next_flag() {
+            match flag.as_str() {
+                "--trace" => {
+                    params.trace = match args.value::<String>(&flag).as_str() {
+                        "-" => Some(Trace::Stdout),
+                        path => Some(Trace::File(path.to_string())),
+                    }
+                }
+                "--no-trace" => params.trace = None,
+                "--crt" => params.crt = true,
+                "--no-crt" => params.crt = false,
+                "--break-cycle" => params
+                    .breakpoints
+                    .push(Breakpoint::at_cycle(args.value(&flag))),
+                "--break-x" => params
+                    .breakpoints
+                    .push(Breakpoint::when_x(args.value(&flag))),
+                _ => args.unknown(&flag),
+            }
+        }
+        params
+    }
 }
 
 fn main() {
+    let params = Params::from_args();
     let program = read_input();
-    let mut cpu = CPU::new(program.as_slice());
-    let mut signal_strength = 0;
-    let smol = program.len() < 10;
-
-    if smol {
-        println!("{}", cpu);
-    }
-
-    while !cpu.is_done() {
-        let cycle = cpu.cycle;
-
-        // part 2
-        if !smol {
-            let sprite = cpu.x - 1..=cpu.x + 1;
-            let position = (cycle - 1) as i32 % 40;
-            print!("{}", if sprite.contains(&position) { '#' } else { '.' });
-            if cycle % 40 == 0 {
-                println!("");
-            }
+    let mut vm = Vm::new(program.as_slice());
+    match params.trace {
+        Some(Trace::Stdout) => vm.trace_to(Box::new(io::stdout())),
+        Some(Trace::File(path)) => {
+            vm.trace_to(Box::new(BufWriter::new(File::create(path).unwrap())))
         }
+        None => (),
+    }
+    for breakpoint in params.breakpoints {
+        vm.add_breakpoint(breakpoint);
+    }
 
-        cpu.step();
-
-        if smol {
-            println!("{}", cpu);
-        }
+    let mut signal_strength = SignalStrength::default();
+    let mut crt = Crt::default();
+    while let Stop::Breakpoint(idx) = vm.run(&mut [&mut signal_strength, &mut crt]) {
+        println!("break at {}: {}", vm.breakpoint(idx).name, vm.state());
+    }
 
-        // part 1
-        if cycle == 20 || (cycle > 20 && (cycle - 20) % 40 == 0) {
-            signal_strength += cycle * cpu.x as usize;
+    println!("part1: {}", signal_strength.total);
+    if params.crt {
+        print!("{}", crt);
+        match ocr::recognise(&crt.rows) {
+            Ok(text) => println!("part2: {}", text),
+            Err(err) => println!("part2: {}", err),
         }
     }
-    println!("part1: {}", signal_strength);
 }
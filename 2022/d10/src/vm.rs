@@ -0,0 +1,288 @@
+use std::{fmt::Display, io::Write, str::FromStr};
+
+pub struct OpInfo {
+    pub mnemonic: &'static str,
+    pub cycles: usize,
+    pub args: usize,
+    // builds the instruction from its arguments
+    decode: fn(&[&str]) -> Result<Instruction, String>,
+}
+
+pub const INSTRUCTION_SET: [OpInfo; 2] = [
+    OpInfo {
+        mnemonic: "noop",
+        cycles: 1,
+        args: 0,
+        decode: |_| Ok(Instruction::Noop),
+    },
+    OpInfo {
+        mnemonic: "addx",
+        cycles: 2,
+        args: 1,
+        decode: |args| match args[0].parse::<i32>() {
+            Ok(value) => Ok(Instruction::AddX { value }),
+            Err(or) => Err(format!("Invalid argument: {}", or)),
+        },
+    },
+];
+
+#[derive(Debug)]
+pub enum Instruction {
+    Noop,
+    AddX { value: i32 },
+}
+
+impl Instruction {
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Instruction::Noop => "noop",
+            Instruction::AddX { .. } => "addx",
+        }
+    }
+
+    pub fn info(&self) -> &'static OpInfo {
+        INSTRUCTION_SET
+            .iter()
+            .find(|info| info.mnemonic == self.mnemonic())
+            .expect("instruction missing from INSTRUCTION_SET")
+    }
+
+    // runs once the instruction's last cycle is over
+    fn retire(&self, state: &mut State) {
+        match self {
+            Instruction::Noop => (),
+            Instruction::AddX { value } => state.x += value,
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s.split(' ').collect::<Vec<_>>();
+        let info = INSTRUCTION_SET
+            .iter()
+            .find(|info| info.mnemonic == words[0])
+            .ok_or(format!("Parse error: {}", s))?;
+        if words.len() != info.args + 1 {
+            return Err(format!(
+                "{} takes {} arguments: {}",
+                info.mnemonic, info.args, s
+            ));
+        }
+        (info.decode)(&words[1..])
+    }
+}
+
+/// Registers during a cycle.
+#[derive(Debug, Clone, Copy)]
+pub struct State {
+    pub cycle: usize,
+    pub pc: usize,
+    pub instr_cycle: usize, // cycles into current instruction
+    pub x: i32,
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "c{}: PC={}.{} X={}",
+            self.cycle, self.pc, self.instr_cycle, self.x
+        )
+    }
+}
+
+/// Gets to see the registers during every cycle.
+pub trait Observer {
+    fn cycle(&mut self, state: &State);
+}
+
+/// Stops the VM when its condition becomes true, not again until it has been false.
+pub struct Breakpoint {
+    pub name: String,
+    condition: Box<dyn Fn(&State) -> bool>,
+    holding: bool, // condition was true when last checked
+}
+
+impl Breakpoint {
+    pub fn new(name: &str, condition: impl Fn(&State) -> bool + 'static) -> Self {
+        Breakpoint {
+            name: name.to_string(),
+            condition: Box::new(condition),
+            holding: false,
+        }
+    }
+
+    // true if the condition just became true
+    fn check(&mut self, state: &State) -> bool {
+        let was_holding = self.holding;
+        self.holding = (self.condition)(state);
+        self.holding && !was_holding
+    }
+
+    pub fn at_cycle(cycle: usize) -> Self {
+        Self::new(&format!("cycle {}", cycle), move |s| s.cycle == cycle)
+    }
+
+    pub fn when_x(x: i32) -> Self {
+        Self::new(&format!("X={}", x), move |s| s.x == x)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Stop {
+    Halted,
+    Breakpoint(usize),
+}
+
+pub struct Vm<'a> {
+    program: &'a [Instruction],
+    state: State,
+    breakpoints: Vec<Breakpoint>,
+    trace: Option<Box<dyn Write>>,
+}
+
+impl<'a> Vm<'a> {
+    pub fn new(program: &'a [Instruction]) -> Self {
+        Vm {
+            program,
+            state: State {
+                cycle: 1,
+                pc: 0,
+                instr_cycle: 0,
+                x: 1,
+            },
+            breakpoints: vec![],
+            trace: None,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        self.breakpoints.push(breakpoint);
+        self.breakpoints.len() - 1
+    }
+
+    pub fn breakpoint(&self, index: usize) -> &Breakpoint {
+        &self.breakpoints[index]
+    }
+
+    /// Writes the registers of every cycle to `out`.
+    pub fn trace_to(&mut self, out: Box<dyn Write>) {
+        self.trace = Some(out);
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state.pc == self.program.len()
+    }
+
+    /// Runs a single cycle.
+    pub fn step(&mut self, observers: &mut [&mut dyn Observer]) {
+        assert!(!self.is_done());
+        if let Some(trace) = self.trace.as_mut() {
+            writeln!(trace, "{}", self.state).unwrap();
+        }
+        for observer in observers.iter_mut() {
+            observer.cycle(&self.state);
+        }
+        let instruction = &self.program[self.state.pc];
+        self.state.instr_cycle += 1;
+        if self.state.instr_cycle == instruction.info().cycles {
+            instruction.retire(&mut self.state);
+            self.state.pc += 1;
+            self.state.instr_cycle = 0;
+        }
+        self.state.cycle += 1;
+    }
+
+    /// Runs until the program ends or a breakpoint triggers before a cycle.
+    /// Running again continues past the breakpoint.
+    pub fn run(&mut self, observers: &mut [&mut dyn Observer]) -> Stop {
+        while !self.is_done() {
+            // breakpoints after one that triggers are checked when resuming
+            let state = self.state;
+            if let Some(idx) = self.breakpoints.iter_mut().position(|b| b.check(&state)) {
+                return Stop::Breakpoint(idx);
+            }
+            self.step(observers);
+        }
+        if let Some(trace) = self.trace.as_mut() {
+            writeln!(trace, "{}", self.state).unwrap();
+            trace.flush().unwrap();
+        }
+        Stop::Halted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn program(lines: &[&str]) -> Vec<Instruction> {
+        lines.iter().map(|ln| ln.parse().unwrap()).collect()
+    }
+
+    // cycles where the VM stopped, until it halts
+    fn stops(vm: &mut Vm) -> Vec<(usize, usize)> {
+        let mut stops = vec![];
+        while let Stop::Breakpoint(idx) = vm.run(&mut []) {
+            stops.push((idx, vm.state().cycle));
+        }
+        stops
+    }
+
+    #[test]
+    fn test_parse() {
+        let program = program(&["noop", "addx -5"]);
+        assert_eq!(program[1].info().cycles, 2);
+        assert_eq!(program[1].mnemonic(), "addx");
+        assert!("addx".parse::<Instruction>().is_err());
+        assert!("addx x".parse::<Instruction>().is_err());
+        assert!("jump 1".parse::<Instruction>().is_err());
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        // X: 1 during cycles 1-3, 4 during 4-5, 1 during 6-7, 4 from 8
+        let program = program(&["noop", "addx 3", "addx -3", "addx 3", "noop"]);
+        let mut vm = Vm::new(&program);
+        vm.add_breakpoint(Breakpoint::when_x(4));
+        vm.add_breakpoint(Breakpoint::at_cycle(4));
+        assert_eq!(stops(&mut vm), vec![(0, 4), (1, 4), (0, 8)]);
+        assert!(vm.is_done());
+        assert_eq!(vm.state().x, 4);
+    }
+
+    #[test]
+    fn test_trace() {
+        let program = program(&["noop", "addx 3"]);
+        let mut vm = Vm::new(&program);
+        let buffer = SharedBuffer::default();
+        vm.trace_to(Box::new(buffer.clone()));
+        assert_eq!(vm.run(&mut []), Stop::Halted);
+        let trace = String::from_utf8(buffer.0.borrow().clone()).unwrap();
+        assert_eq!(
+            trace,
+            "c1: PC=0.0 X=1\nc2: PC=1.0 X=1\nc3: PC=1.1 X=1\nc4: PC=2.0 X=4\n"
+        );
+    }
+}
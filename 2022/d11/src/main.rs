@@ -1,209 +1,91 @@
-use std::{
-    io::{self, Read},
-    mem,
-    str::FromStr,
-};
+mod monkey;
 
-#[derive(Debug)]
-enum Operation {
-    Add(u32),
-    Mul(u32),
-    Pow2(),
-}
-
-impl Operation {
-    fn apply(&self, value: u64) -> u64 {
-        match self {
-            Operation::Add(x) => value + (*x as u64),
-            Operation::Mul(x) => value * (*x as u64),
-            Operation::Pow2() => value * value,
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Behaviour {
-    initial_items: Vec<u32>,
-    operation: Operation,
-    divisor: u32,
-    dst_true: usize,
-    dst_false: usize,
-}
+use std::io::{self, Read};
 
-struct Monkey<'a> {
-    items: Vec<u32>,
-    items_inspected: usize,
-    behaviour: &'a Behaviour,
-}
+use monkey::{Behaviour, Relieved, Residues};
 
-impl Behaviour {
-    fn monkey(&self) -> Monkey {
-        Monkey {
-            items: self.initial_items.clone(),
-            items_inspected: 0,
-            behaviour: self,
-        }
+fn print_inspected(inspections: &[u64]) {
+    for (idx, count) in inspections.iter().enumerate() {
+        println!("Monkey {} inspected items {} times.", idx, count);
     }
 }
 
-impl FromStr for Behaviour {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.split('\n');
-        assert!(lines.next().unwrap().starts_with("Monkey "));
-
-        let line1 = lines.next().unwrap();
-        assert!(line1.starts_with("  Starting items: "));
-        let initial_items = line1[18..]
-            .split(|c| c == ' ' || c == ',')
-            .filter(|w| !w.is_empty())
-            .map(|w| w.parse::<u32>().unwrap())
-            .collect::<Vec<_>>();
-
-        let operation = Operation::from_str(lines.next().unwrap()).unwrap();
-
-        let line3 = lines.next().unwrap();
-        assert!(line3.starts_with("  Test: divisible by "));
-        let divisor = line3.split(' ').last().unwrap().parse::<u32>().unwrap();
-
-        let line4 = lines.next().unwrap();
-        assert!(line4.starts_with("    If true: throw to monkey "));
-        let dst_true = line4.split(' ').last().unwrap().parse::<usize>().unwrap();
-
-        let line5 = lines.next().unwrap();
-        assert!(line5.starts_with("    If false: throw to monkey "));
-        let dst_false = line5.split(' ').last().unwrap().parse::<usize>().unwrap();
-
-        Ok(Behaviour {
-            initial_items,
-            operation,
-            divisor,
-            dst_true,
-            dst_false,
-        })
-    }
+fn read_input() -> Result<Vec<Behaviour>, String> {
+    let mut buf = String::new();
+    io::stdin()
+        .read_to_string(&mut buf)
+        .map_err(|err| err.to_string())?;
+    buf.split("\n\n")
+        .filter(|s| !s.trim().is_empty())
+        .map(str::parse)
+        .collect()
 }
 
-impl FromStr for Operation {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        assert!(s.starts_with("  Operation: new = old "));
-        let mut words = s[23..].split(' ');
-        let op = {
-            let op_word = words.next().unwrap();
-            assert!(op_word.eq("*") || op_word.eq("+"));
-            op_word.chars().next().unwrap()
-        };
-        let operand = words.next().unwrap();
-        let operand_parsed = operand.parse::<u32>();
-        assert!(operand.eq("old") || operand_parsed.is_ok());
-        match (op, operand_parsed.ok()) {
-            ('+', Some(value)) => Ok(Operation::Add(value)),
-            ('*', Some(value)) => Ok(Operation::Mul(value)),
-            ('*', None) => Ok(Operation::Pow2()),
-            _ => Err(format!("Invalid operation line: {}", s)),
+fn main() {
+    let mut rounds = vec![];
+    let mut trace = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--trace" => {
+                trace = Some(
+                    args.next()
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .expect("--trace takes an item number"),
+                )
+            }
+            n => rounds.push(n.parse::<u64>().expect("Invalid number of rounds")),
         }
     }
-}
-
-enum GameMode {
-    Short,
-    Long { common_divisor: u64 },
-}
-
-fn play_round(monkeys: &mut [Monkey], mode: &GameMode) {
-    for i in 0..monkeys.len() {
-        let items = mem::take(&mut monkeys[i].items);
-        monkeys[i].items_inspected += items.len();
-        let mb = monkeys[i].behaviour;
-        for item in items {
-            let new_item = match mode {
-                GameMode::Long { common_divisor } => {
-                    (mb.operation.apply(item as u64) % common_divisor) as u32
-                }
-                GameMode::Short => (mb.operation.apply(item as u64) / 3) as u32,
-            };
-            let dst = if new_item % mb.divisor == 0 {
-                mb.dst_true
-            } else {
-                mb.dst_false
-            };
-            monkeys[dst].items.push(new_item);
-        }
+    if rounds.is_empty() {
+        rounds.push(10000);
     }
-}
-
-fn play_rounds(rounds: usize, behaviours: &[Behaviour]) -> usize {
-    let mode = if rounds > 1000 {
-        let common_divisor = behaviours.iter().fold(1, |acc, b| acc * b.divisor) as u64;
-        GameMode::Long { common_divisor }
-    } else {
-        GameMode::Short
+    let troop = read_input().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let fail = |err: String| -> Vec<u64> {
+        eprintln!("{}", err);
+        std::process::exit(1);
     };
 
-    let mut vec_monkeys = behaviours.iter().map(|b| b.monkey()).collect::<Vec<_>>();
-    let mut monkeys = vec_monkeys.as_mut();
-
-    for r in 0..rounds {
-        play_round(&mut monkeys, &mode);
-        if r == 0 || r == 19 || (r > 20 && r % 1000 == 999) {
-            println!("After round {}", r + 1);
-            print_inspected(monkeys);
-        }
-    }
-    if rounds % 1000 != 0 && rounds != 1 && rounds != 20 {
-        println!("After round {}", rounds);
-        print_inspected(monkeys);
-    }
-
-    business_level(monkeys)
-}
+    let part1 = monkey::inspections::<Relieved>(&troop, 20).unwrap_or_else(fail);
+    println!("After round 20");
+    print_inspected(&part1);
+    println!("Part 1: {}", monkey::business_level(&part1));
 
-fn print_state(monkeys: &[Monkey]) {
-    for (idx, monkey) in monkeys.iter().enumerate() {
-        println!("Monkey {}: {:?}", idx, monkey.items);
+    for &n in rounds.iter() {
+        let part2 = monkey::inspections::<Residues>(&troop, n).unwrap_or_else(fail);
+        println!("---\nAfter round {} without relief", n);
+        print_inspected(&part2);
+        println!("Part 2: {}", monkey::business_level(&part2));
     }
-}
 
-fn print_inspected(monkeys: &[Monkey]) {
-    for (idx, monkey) in monkeys.iter().enumerate() {
-        println!(
-            "Monkey {} inspected items {} times.",
-            idx, monkey.items_inspected
-        );
+    if let Some(id) = trace {
+        let items = monkey::items(&troop);
+        let item = *items.get(id).unwrap_or_else(|| {
+            eprintln!("There are only {} items", items.len());
+            std::process::exit(1);
+        });
+        println!("---\nItem {} ({} at monkey {}):", id, item.1, item.0);
+        let trip = monkey::lineage::<Relieved>(&troop, item, 20).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        });
+        for (r, path) in trip.iter().enumerate() {
+            println!("round {}: {:?}", r + 1, path);
+        }
+        let last = rounds.iter().max().unwrap();
+        match monkey::follow::<Residues>(&troop, item, *last) {
+            Ok(report) => match report.cycle {
+                Some((start, len)) => println!(
+                    "without relief, repeats every {} rounds from round {}",
+                    len,
+                    start + 1
+                ),
+                None => println!("without relief, doesn't repeat in {} rounds", last),
+            },
+            Err(err) => eprintln!("{}", err),
+        }
     }
 }
-
-fn business_level(monkeys: &[Monkey]) -> usize {
-    let mut inspected = monkeys
-        .iter()
-        .map(|m| m.items_inspected)
-        .collect::<Vec<_>>();
-    inspected.sort();
-    inspected.reverse();
-    inspected[0] * inspected[1]
-}
-
-fn read_input() -> Vec<Behaviour> {
-    let mut buf = String::new();
-    io::stdin()
-        .read_to_string(&mut buf)
-        .expect("Error reading input");
-    buf.split("\n\n")
-        .map(Behaviour::from_str)
-        .map(Result::unwrap)
-        .collect()
-}
-
-fn main() {
-    let input = read_input();
-
-    let part1 = play_rounds(20, input.as_slice());
-    println!("---");
-    let part2 = play_rounds(10000, input.as_slice());
-
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
-}
@@ -0,0 +1,326 @@
+use std::{collections::HashMap, hash::Hash, str::FromStr};
+
+#[derive(Debug)]
+pub enum Operation {
+    Add(u32),
+    Mul(u32),
+    Square,
+}
+
+#[derive(Debug)]
+pub struct Behaviour {
+    pub initial_items: Vec<u32>,
+    pub operation: Operation,
+    pub divisor: u32,
+    pub dst_true: usize,
+    pub dst_false: usize,
+}
+
+impl Operation {
+    fn apply(&self, value: u64) -> Option<u64> {
+        match self {
+            Operation::Add(x) => value.checked_add(*x as u64),
+            Operation::Mul(x) => value.checked_mul(*x as u64),
+            Operation::Square => value.checked_mul(value),
+        }
+    }
+
+    fn apply_mod(&self, residue: u32, modulus: u32) -> u32 {
+        let (r, m) = (residue as u64, modulus as u64);
+        (match self {
+            Operation::Add(x) => (r + *x as u64) % m,
+            Operation::Mul(x) => (r * *x as u64) % m,
+            Operation::Square => (r * r) % m,
+        }) as u32
+    }
+}
+
+impl FromStr for Behaviour {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let mut line = |prefix: &str| {
+            lines
+                .next()
+                .and_then(|ln| ln.trim().strip_prefix(prefix))
+                .ok_or(format!("Expected '{}' in: {}", prefix, s))
+        };
+        line("Monkey ")?;
+        let initial_items = line("Starting items:")?
+            .split(',')
+            .map(|w| w.trim().parse::<u32>().map_err(|err| err.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let operation = line("Operation: new = old ")?.parse()?;
+        let number = |w: &str| w.parse::<u32>().map_err(|err| err.to_string());
+        let divisor = number(line("Test: divisible by ")?)?;
+        if divisor == 0 {
+            return Err("Divisor can't be 0".to_string());
+        }
+        let dst_true = number(line("If true: throw to monkey ")?)? as usize;
+        let dst_false = number(line("If false: throw to monkey ")?)? as usize;
+
+        Ok(Behaviour {
+            initial_items,
+            operation,
+            divisor,
+            dst_true,
+            dst_false,
+        })
+    }
+}
+
+impl FromStr for Operation {
+    type Err = String;
+
+    // "* 19", "+ 6", "* old"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(' ') {
+            Some(("*", "old")) => Ok(Operation::Square),
+            Some(("+", x)) => x.parse().map(Operation::Add).map_err(|e| e.to_string()),
+            Some(("*", x)) => x.parse().map(Operation::Mul).map_err(|e| e.to_string()),
+            _ => Err(format!("Invalid operation: {}", s)),
+        }
+    }
+}
+
+/// A worry level as far as the monkeys can tell.
+pub trait Worry: Clone + Eq + Hash {
+    fn new(value: u32, troop: &[Behaviour]) -> Self;
+    /// The worry after `monkey` inspects the item, `None` on overflow.
+    fn inspect(&self, monkey: usize, troop: &[Behaviour]) -> Option<Self>;
+    fn is_divisible(&self, monkey: usize, troop: &[Behaviour]) -> bool;
+}
+
+/// Exact worry, divided by 3 after each inspection.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Relieved(pub u64);
+
+/// Worry modulo each monkey's divisor, which is all the tests look at.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Residues(Vec<u32>);
+
+impl Worry for Relieved {
+    fn new(value: u32, _: &[Behaviour]) -> Self {
+        Relieved(value as u64)
+    }
+
+    fn inspect(&self, monkey: usize, troop: &[Behaviour]) -> Option<Self> {
+        troop[monkey]
+            .operation
+            .apply(self.0)
+            .map(|value| Relieved(value / 3))
+    }
+
+    fn is_divisible(&self, monkey: usize, troop: &[Behaviour]) -> bool {
+        self.0.is_multiple_of(troop[monkey].divisor as u64)
+    }
+}
+
+impl Worry for Residues {
+    fn new(value: u32, troop: &[Behaviour]) -> Self {
+        Residues(troop.iter().map(|b| value % b.divisor).collect())
+    }
+
+    fn inspect(&self, monkey: usize, troop: &[Behaviour]) -> Option<Self> {
+        let operation = &troop[monkey].operation;
+        Some(Residues(
+            self.0
+                .iter()
+                .zip(troop)
+                .map(|(&r, b)| operation.apply_mod(r, b.divisor))
+                .collect(),
+        ))
+    }
+
+    fn is_divisible(&self, monkey: usize, _: &[Behaviour]) -> bool {
+        self.0[monkey] == 0
+    }
+}
+
+/// Every item with the monkey holding it at the start, numbered in input order.
+pub fn items(troop: &[Behaviour]) -> Vec<(usize, u32)> {
+    troop
+        .iter()
+        .enumerate()
+        .flat_map(|(idx, b)| b.initial_items.iter().map(move |&v| (idx, v)))
+        .collect()
+}
+
+/// Plays one round for a single item: items don't affect each other, so its trip only
+/// depends on who holds it and its worry.
+/// Returns the monkeys that inspected it, in order.
+fn round<W: Worry>(
+    troop: &[Behaviour],
+    monkey: &mut usize,
+    worry: &mut W,
+) -> Result<Vec<usize>, String> {
+    let mut path = vec![];
+    loop {
+        let m = *monkey;
+        *worry = worry
+            .inspect(m, troop)
+            .ok_or(format!("Worry overflows at monkey {}", m))?;
+        path.push(m);
+        let b = &troop[m];
+        *monkey = if worry.is_divisible(m, troop) {
+            b.dst_true
+        } else {
+            b.dst_false
+        };
+        if *monkey >= troop.len() {
+            return Err(format!("Monkey {} throws to missing monkey {}", m, monkey));
+        }
+        if *monkey <= m {
+            // waits for the next round
+            return Ok(path);
+        }
+    }
+}
+
+/// The monkeys that inspected an item, round by round.
+pub fn lineage<W: Worry>(
+    troop: &[Behaviour],
+    item: (usize, u32),
+    rounds: usize,
+) -> Result<Vec<Vec<usize>>, String> {
+    let (mut monkey, mut worry) = (item.0, W::new(item.1, troop));
+    (0..rounds)
+        .map(|_| round(troop, &mut monkey, &mut worry))
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ItemReport {
+    /// Times each monkey inspected the item.
+    pub inspections: Vec<u64>,
+    /// First round of the repeating part of the item's trip, and its length.
+    pub cycle: Option<(u64, u64)>,
+}
+
+/// Follows an item for `rounds` rounds, skipping ahead once its trip starts repeating.
+pub fn follow<W: Worry>(
+    troop: &[Behaviour],
+    item: (usize, u32),
+    rounds: u64,
+) -> Result<ItemReport, String> {
+    let (mut monkey, mut worry) = (item.0, W::new(item.1, troop));
+    let mut seen = HashMap::new();
+    // inspections before each round
+    let mut history = vec![vec![0u64; troop.len()]];
+    for r in 0..rounds {
+        if let Some(start) = seen.insert((monkey, worry.clone()), r) {
+            let (len, remaining) = (r - start, rounds - r);
+            let (at_start, now) = (&history[start as usize], &history[r as usize]);
+            let tail = &history[(start + remaining % len) as usize];
+            let inspections = (0..troop.len())
+                .map(|i| {
+                    now[i] + (remaining / len) * (now[i] - at_start[i]) + tail[i] - at_start[i]
+                })
+                .collect();
+            return Ok(ItemReport {
+                inspections,
+                cycle: Some((start, len)),
+            });
+        }
+        let mut counts = history.last().unwrap().clone();
+        for m in round(troop, &mut monkey, &mut worry)? {
+            counts[m] += 1;
+        }
+        history.push(counts);
+    }
+    Ok(ItemReport {
+        inspections: history.pop().unwrap(),
+        cycle: None,
+    })
+}
+
+/// Times each monkey inspected an item over `rounds` rounds.
+pub fn inspections<W: Worry>(troop: &[Behaviour], rounds: u64) -> Result<Vec<u64>, String> {
+    let mut total = vec![0; troop.len()];
+    for item in items(troop) {
+        let report = follow::<W>(troop, item, rounds)?;
+        total
+            .iter_mut()
+            .zip(report.inspections)
+            .for_each(|(t, n)| *t += n);
+    }
+    Ok(total)
+}
+
+/// Product of the two highest counts, wide enough for any number of rounds.
+pub fn business_level(inspections: &[u64]) -> u128 {
+    let mut inspected = inspections.to_vec();
+    inspected.sort_unstable_by(|a, b| b.cmp(a));
+    inspected.iter().take(2).map(|&n| n as u128).product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    fn troop() -> Vec<Behaviour> {
+        EXAMPLE.split("\n\n").map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let troop = troop();
+        let short = inspections::<Relieved>(&troop, 20).unwrap();
+        assert_eq!(short, vec![101, 95, 7, 105]);
+        let long = inspections::<Residues>(&troop, 10000).unwrap();
+        assert_eq!(long, vec![52166, 47830, 1938, 52013]);
+        assert_eq!(business_level(&long), 2713310158);
+    }
+
+    #[test]
+    fn test_cycles_match_rounds() {
+        let troop = troop();
+        for item in items(&troop) {
+            let report = follow::<Residues>(&troop, item, 5000).unwrap();
+            let (start, len) = report.cycle.unwrap();
+            assert!(start + len <= 5000);
+            let mut counts = vec![0; troop.len()];
+            for m in lineage::<Residues>(&troop, item, 5000).unwrap().concat() {
+                counts[m] += 1;
+            }
+            assert_eq!(report.inspections, counts);
+        }
+    }
+
+    #[test]
+    fn test_lineage() {
+        let troop = troop();
+        // 79 -> 500 -> 167, then 57 -> 1083 -> 362
+        let trip = lineage::<Relieved>(&troop, (0, 79), 2).unwrap();
+        assert_eq!(trip, vec![vec![0, 3], vec![1, 2, 3]]);
+    }
+}
@@ -0,0 +1,352 @@
+use std::{cmp::Ordering, fmt::Display, iter::Peekable, str::CharIndices, str::FromStr};
+
+use num_bigint::BigUint;
+use serde_json::{Number, Value};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    Integer(BigUint),
+    List(Vec<Packet>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset of the problem.
+    pub position: usize,
+    pub expected: &'static str,
+    pub found: Option<char>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ConversionError {
+    /// Only lists and non-negative integers are packets.
+    NotAPacket(Value),
+}
+
+/// How a comparison was decided.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decision {
+    pub order: Ordering,
+    /// Indices into the nested lists down to where the packets first differ,
+    /// or empty if they are equal.
+    pub path: Vec<usize>,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.found {
+            Some(c) => write!(
+                f,
+                "at {}: expected {}, found '{}'",
+                self.position, self.expected, c
+            ),
+            None => write!(
+                f,
+                "at {}: expected {}, found end of input",
+                self.position, self.expected
+            ),
+        }
+    }
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversionError::NotAPacket(value) => write!(f, "{} is not a packet", value),
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    len: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str) -> Self {
+        Parser {
+            chars: s.char_indices().peekable(),
+            len: s.len(),
+        }
+    }
+
+    // position and char of the next token, skipping whitespace
+    fn peek(&mut self) -> (usize, Option<char>) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        match self.chars.peek() {
+            Some(&(pos, c)) => (pos, Some(c)),
+            None => (self.len, None),
+        }
+    }
+
+    fn error<T>(&mut self, expected: &'static str) -> Result<T, ParseError> {
+        let (position, found) = self.peek();
+        Err(ParseError {
+            position,
+            expected,
+            found,
+        })
+    }
+
+    fn packet(&mut self) -> Result<Packet, ParseError> {
+        match self.peek() {
+            (_, Some('[')) => self.list(),
+            (_, Some('0'..='9')) => Ok(self.integer()),
+            _ => self.error("opening bracket or digit"),
+        }
+    }
+
+    fn integer(&mut self) -> Packet {
+        let mut digits = vec![];
+        while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_digit()) {
+            digits.push(c as u8 - b'0');
+        }
+        Packet::Integer(BigUint::from_radix_be(&digits, 10).unwrap())
+    }
+
+    fn list(&mut self) -> Result<Packet, ParseError> {
+        self.chars.next(); // [
+        let mut items = vec![];
+        if self.peek().1 == Some(']') {
+            self.chars.next();
+            return Ok(Packet::List(items));
+        }
+        loop {
+            items.push(self.packet()?);
+            match self.peek().1 {
+                Some(',') => self.chars.next(),
+                Some(']') => {
+                    self.chars.next();
+                    return Ok(Packet::List(items));
+                }
+                _ => return self.error("comma or closing bracket"),
+            };
+        }
+    }
+}
+
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let packet = parser.packet()?;
+        match parser.peek() {
+            (_, None) => Ok(packet),
+            _ => parser.error("end of input"),
+        }
+    }
+}
+
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Integer(x) => write!(f, "{}", x),
+            Packet::List(items) => {
+                write!(f, "[")?;
+                for (idx, packet) in items.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", packet)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl From<u32> for Packet {
+    fn from(value: u32) -> Self {
+        Packet::Integer(value.into())
+    }
+}
+
+impl From<&Packet> for Value {
+    fn from(packet: &Packet) -> Self {
+        match packet {
+            Packet::Integer(x) => Value::Number(x.to_string().parse::<Number>().unwrap()),
+            Packet::List(items) => Value::Array(items.iter().map(Value::from).collect()),
+        }
+    }
+}
+
+impl TryFrom<&Value> for Packet {
+    type Error = ConversionError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(n) => n
+                .to_string()
+                .parse::<BigUint>()
+                .map(Packet::Integer)
+                .map_err(|_| ConversionError::NotAPacket(value.clone())),
+            Value::Array(items) => Ok(Packet::List(
+                items
+                    .iter()
+                    .map(Packet::try_from)
+                    .collect::<Result<_, _>>()?,
+            )),
+            _ => Err(ConversionError::NotAPacket(value.clone())),
+        }
+    }
+}
+
+impl Packet {
+    pub fn divider(value: u32) -> Self {
+        Packet::List(vec![Packet::List(vec![Packet::from(value)])])
+    }
+
+    /// Compares like `cmp`, also telling where the packets first differ.
+    pub fn decide(&self, other: &Self) -> Decision {
+        let mut path = vec![];
+        let order = Self::compare(self, other, &mut path);
+        if order.is_eq() {
+            path.clear();
+        }
+        Decision { order, path }
+    }
+
+    fn compare(left: &Self, right: &Self, path: &mut Vec<usize>) -> Ordering {
+        let (left, right) = match (left, right) {
+            (Packet::Integer(x), Packet::Integer(y)) => return x.cmp(y),
+            (Packet::List(x), Packet::List(y)) => (x.as_slice(), y.as_slice()),
+            // an integer compares as a list holding just itself
+            (Packet::List(x), y) => (x.as_slice(), std::slice::from_ref(y)),
+            (x, Packet::List(y)) => (std::slice::from_ref(x), y.as_slice()),
+        };
+        for idx in 0..left.len().max(right.len()) {
+            path.push(idx);
+            let order = match (left.get(idx), right.get(idx)) {
+                (Some(x), Some(y)) => Self::compare(x, y, path),
+                (None, _) => Ordering::Less,
+                (_, None) => Ordering::Greater,
+            };
+            if order.is_ne() {
+                return order;
+            }
+            path.pop();
+        }
+        Ordering::Equal
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Self::compare(self, other, &mut vec![])
+    }
+}
+
+/// 1-based index of every pair in the right order, and where each was decided.
+pub fn ordered_pairs(packets: &[Packet]) -> Vec<(usize, Vec<usize>)> {
+    packets
+        .chunks(2)
+        .enumerate()
+        .filter_map(|(idx, pair)| {
+            let decision = pair[0].decide(pair.get(1)?);
+            decision.order.is_lt().then_some((idx + 1, decision.path))
+        })
+        .collect()
+}
+
+/// 1-based positions `dividers` would have if added to `packets` and sorted,
+/// without sorting anything.
+pub fn sorted_positions(packets: &[Packet], dividers: &[Packet]) -> Vec<usize> {
+    dividers
+        .iter()
+        .map(|d| {
+            let before = packets.iter().chain(dividers).filter(|p| *p < d).count();
+            before + 1
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]";
+
+    fn example() -> Vec<Packet> {
+        EXAMPLE
+            .lines()
+            .filter(|ln| !ln.is_empty())
+            .map(|ln| ln.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let packets = example();
+        let pairs = ordered_pairs(&packets);
+        assert_eq!(
+            pairs,
+            vec![(1, vec![2]), (2, vec![1, 0]), (4, vec![3]), (6, vec![0])]
+        );
+        let dividers = [Packet::divider(2), Packet::divider(6)];
+        assert_eq!(sorted_positions(&packets, &dividers), vec![10, 14]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |position, expected, found| {
+            Err(ParseError {
+                position,
+                expected,
+                found,
+            })
+        };
+        assert_eq!(
+            "[1,[2".parse::<Packet>(),
+            error(5, "comma or closing bracket", None)
+        );
+        assert_eq!(
+            "[1,,2]".parse::<Packet>(),
+            error(3, "opening bracket or digit", Some(','))
+        );
+        assert_eq!(
+            "[1]]".parse::<Packet>(),
+            error(3, "end of input", Some(']'))
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let text = "[[1,[]],123456789012345678901234567890]";
+        let packet: Packet = text.parse().unwrap();
+        let value = Value::from(&packet);
+        assert_eq!(value.to_string(), text);
+        assert_eq!(Packet::try_from(&value), Ok(packet));
+
+        let value: Value = serde_json::from_str("[1,-2]").unwrap();
+        assert!(Packet::try_from(&value).is_err());
+    }
+}
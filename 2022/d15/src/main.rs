@@ -1,3 +1,5 @@
+mod rotated;
+
 use std::{
     collections::HashSet,
     io::{self, BufRead},
@@ -5,7 +7,8 @@ use std::{
     str::FromStr,
 };
 
-type Pos = (i32, i32);
+use args::Args;
+use rotated::{Pos, Rect, Region};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct SensorReading {
@@ -34,7 +37,7 @@ fn read_input() -> Vec<SensorReading> {
     io::stdin()
         .lock()
         .lines()
-        .filter_map(Result::ok)
+        .map_while(Result::ok)
         .filter_map(|ln| SensorReading::from_str(&ln).ok())
         .collect()
 }
@@ -56,9 +59,13 @@ impl SensorReading {
             Some(x1..=x2)
         }
     }
+
+    fn diamond(&self) -> Rect {
+        Rect::diamond(self.sensor, self.range())
+    }
 }
 
-fn count_x_where_no_beacon(readings: &Vec<SensorReading>, y: i32) -> usize {
+fn count_x_where_no_beacon(readings: &[SensorReading], y: i32) -> usize {
     let mut ranges = readings
         .iter()
         .filter_map(|sr| sr.range_at_row(y))
@@ -79,76 +86,34 @@ fn count_x_where_no_beacon(readings: &Vec<SensorReading>, y: i32) -> usize {
         - beacons_on_line
 }
 
-fn find_empty_spot(mut rs: &[RangeInclusive<i32>], max: i32) -> Option<i32> {
-    // skip before zero
-    while !rs.is_empty() && rs[0].end().lt(&0) {
-        rs = &rs[1..];
-    }
-    if rs.is_empty() {
-        return None;
-    }
-
-    let mut candidate = 0;
-    while !rs.is_empty() {
-        let (start, end) = (*rs[0].start(), *rs[0].end());
-        if start > candidate {
-            return Some(candidate);
-        } else if end >= max {
-            return None;
-        }
-        if end > candidate {
-            candidate = end + 1;
-        }
-        rs = &rs[1..];
-    }
-    None
-}
-
-fn find_distress_signal(readings: &Vec<SensorReading>, max: i32) -> Option<Pos> {
-    for y in 0..=max {
-        let mut ranges = readings
-            .iter()
-            .filter_map(|sr| sr.range_at_row(y))
-            .collect::<Vec<_>>();
-        ranges.sort_by_key(|r| *r.start());
-        if let Some(x) = find_empty_spot(ranges.as_slice(), max) {
-            return Some((x, y));
-        }
-    }
-
-    None
-}
-
-fn print_field(readings: &Vec<SensorReading>, range: RangeInclusive<Pos>) {
-    let x_range = range.start().0..=range.end().0;
-    let y_range = range.start().1..=range.end().1;
-    let sensors = readings.iter().map(|sr| sr.sensor).collect::<HashSet<_>>();
-    let beacons = readings
-        .iter()
-        .map(|sr| sr.closest_beacon)
-        .collect::<HashSet<_>>();
-    for y in y_range {
-        let ranges = readings
-            .iter()
-            .filter_map(|sr| sr.range_at_row(y))
-            .collect::<Vec<_>>();
-        print!("{:<4}", y);
-        for x in x_range.clone() {
-            let pos = (x, y);
+/// Prints `window` with one character for every `scale` by `scale` block:
+/// sensors, beacons, `#` if fully covered and `.` if any of it is not.
+fn print_field(readings: &[SensorReading], window: RangeInclusive<Pos>, scale: i32) {
+    let (start, end) = (*window.start(), *window.end());
+    let diamonds = readings.iter().map(|sr| sr.diamond()).collect::<Vec<_>>();
+    let label_width = start.1.to_string().len().max(end.1.to_string().len()) + 1;
+    for y in (start.1..=end.1).step_by(scale as usize) {
+        print!("{:<w$}", y, w = label_width);
+        for x in (start.0..=end.0).step_by(scale as usize) {
+            let block = Region {
+                x: x..=(x + scale - 1).min(end.0),
+                y: y..=(y + scale - 1).min(end.1),
+            };
+            let inside = |p: &Pos| block.x.contains(&p.0) && block.y.contains(&p.1);
             print!(
                 "{}",
-                if sensors.contains(&pos) {
+                if readings.iter().any(|sr| inside(&sr.sensor)) {
                     'S'
-                } else if beacons.contains(&pos) {
+                } else if readings.iter().any(|sr| inside(&sr.closest_beacon)) {
                     'B'
-                } else if ranges.iter().any(|r| r.contains(&x)) {
-                    '#'
-                } else {
+                } else if block.uncovered(&diamonds).next().is_some() {
                     '.'
+                } else {
+                    '#'
                 }
             )
         }
-        print!("\n");
+        println!();
     }
 }
 
@@ -156,21 +121,82 @@ fn pos_value(pos: Pos) -> u64 {
     (pos.0 as u64 * 4000000u64) + pos.1 as u64
 }
 
+/// Which row to count for part 1, how far to search for the beacon in part 2,
+/// and which part of the field to draw. The example is a 20x20 field.
+#[derive(Debug, Clone)]
+struct Params {
+    row: i32,                            // part 1
+    max: i32,                            // part 2 search area
+    window: Option<RangeInclusive<Pos>>, // field to print
+    scale: i32,                          // field positions per character
+}
+
+impl Params {
+    const REAL: Params = Params {
+        row: 2000000,
+        max: 4000000,
+        window: None,
+        scale: 1,
+    };
+    const EXAMPLE: Params = Params {
+        row: 10,
+        max: 20,
+        window: Some((0, 0)..=(20, 20)),
+        scale: 1,
+    };
+
+    fn from_args() -> Params {
+        let mut args =
+            Args::new("d15 [--example] [--row N] [--max N] [--window X0,Y0,X1,Y1] [--scale N]");
+        let mut params = if args.example() {
+            Params::EXAMPLE
+        } else {
+            Params::REAL
+        };
+        while let Some(flag) = args.next_flag() {
+            match flag.as_str() {
+                "--row" => params.row = args.value(&flag),
+                "--max" => params.max = args.value(&flag),
+                "--window" => {
+                    let v = args.values(&flag, 4);
+                    params.window = Some((v[0], v[1])..=(v[2], v[3]));
+                }
+                "--scale" => params.scale = args.value::<i32>(&flag).max(1),
+                _ => args.unknown(&flag),
+            }
+        }
+        params
+    }
+}
+
 fn main() {
+    let params = Params::from_args();
     let input = read_input();
-    let max = input.iter().map(|sr| sr.sensor.1).max().unwrap();
-    let big = max > 20;
 
     // part 1
-    let line = if big { 2000000 } else { 10 };
-    println!("Part 1: {}", count_x_where_no_beacon(&input, line));
+    println!("Part 1: {}", count_x_where_no_beacon(&input, params.row));
 
-    if !big {
-        print_field(&input, (0, 0)..=(20, 20));
+    if let Some(window) = params.window.clone() {
+        print_field(&input, window, params.scale);
     }
 
     // part 2
-    let max_x = if big { 4000000 } else { 20 };
-    let ds = find_distress_signal(&input, max_x).unwrap();
-    println!("Part 2: {}", pos_value(ds));
+    let region = Region {
+        x: 0..=params.max,
+        y: 0..=params.max,
+    };
+    let diamonds = input.iter().map(|sr| sr.diamond()).collect::<Vec<_>>();
+    // one more than is printed, to tell whether there are more
+    let mut uncovered = region.uncovered(&diamonds).take(11).collect::<Vec<_>>();
+    let more = uncovered.len() > 10;
+    uncovered.truncate(10);
+    match uncovered[..] {
+        [] => println!("Part 2: no distress signal within 0..={}", params.max),
+        [ds] => println!("Part 2: {} at {:?}", pos_value(ds), ds),
+        _ => println!(
+            "Part 2: the signal could be at any of {:?}{}",
+            uncovered,
+            if more { "..." } else { "" }
+        ),
+    }
 }
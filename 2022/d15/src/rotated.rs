@@ -0,0 +1,212 @@
+//! Coordinates rotated by 45°, `u = x + y` and `v = x - y`, where the diamond of
+//! points within a manhattan distance of a sensor is an axis-aligned square.
+//! Points with integer `x` and `y` are the ones where `u` and `v` have the same parity.
+
+use std::ops::RangeInclusive;
+
+pub type Pos = (i32, i32);
+
+/// Inclusive ranges of `u` and `v`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub u: (i32, i32),
+    pub v: (i32, i32),
+}
+
+pub fn rotate(pos: Pos) -> (i32, i32) {
+    (pos.0 + pos.1, pos.0 - pos.1)
+}
+
+pub fn unrotate(u: i32, v: i32) -> Pos {
+    ((u + v) / 2, (u - v) / 2)
+}
+
+impl Rect {
+    pub fn diamond(center: Pos, radius: i32) -> Rect {
+        let (u, v) = rotate(center);
+        Rect {
+            u: (u - radius, u + radius),
+            v: (v - radius, v + radius),
+        }
+    }
+
+    fn intersects(&self, other: &Rect) -> bool {
+        self.u.0 <= other.u.1
+            && other.u.0 <= self.u.1
+            && self.v.0 <= other.v.1
+            && other.v.0 <= self.v.1
+    }
+
+    /// The parts of `self` outside `other`, cut along the edges of `other`.
+    fn subtract(&self, other: &Rect) -> Vec<Rect> {
+        if !self.intersects(other) {
+            return vec![*self];
+        }
+        let mut parts = vec![];
+        if self.u.0 < other.u.0 {
+            parts.push(Rect {
+                u: (self.u.0, other.u.0 - 1),
+                v: self.v,
+            });
+        }
+        if self.u.1 > other.u.1 {
+            parts.push(Rect {
+                u: (other.u.1 + 1, self.u.1),
+                v: self.v,
+            });
+        }
+        let u = (self.u.0.max(other.u.0), self.u.1.min(other.u.1));
+        if self.v.0 < other.v.0 {
+            parts.push(Rect {
+                u,
+                v: (self.v.0, other.v.0 - 1),
+            });
+        }
+        if self.v.1 > other.v.1 {
+            parts.push(Rect {
+                u,
+                v: (other.v.1 + 1, self.v.1),
+            });
+        }
+        parts
+    }
+}
+
+/// Axis-aligned area in regular coordinates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub x: RangeInclusive<i32>,
+    pub y: RangeInclusive<i32>,
+}
+
+impl Region {
+    fn bounding_rect(&self) -> Rect {
+        let (x0, x1, y0, y1) = (
+            *self.x.start(),
+            *self.x.end(),
+            *self.y.start(),
+            *self.y.end(),
+        );
+        Rect {
+            u: (x0 + y0, x1 + y1),
+            v: (x0 - y1, x1 - y0),
+        }
+    }
+
+    // v of the points in `rect` on row `u` that are in the region
+    fn row(&self, rect: &Rect, u: i32) -> (i32, i32) {
+        let (x0, x1, y0, y1) = (
+            *self.x.start(),
+            *self.x.end(),
+            *self.y.start(),
+            *self.y.end(),
+        );
+        let lo = rect.v.0.max(2 * x0 - u).max(u - 2 * y1);
+        let hi = rect.v.1.min(2 * x1 - u).min(u - 2 * y0);
+        // first v with the parity of u
+        (lo + (lo - u).rem_euclid(2), hi)
+    }
+
+    // whether any point of `rect` is in the region: the width of a row is
+    // concave in u, so it is widest where one of the bounds changes slope
+    fn overlaps(&self, rect: &Rect) -> bool {
+        let (x0, x1, y0, y1) = (
+            *self.x.start(),
+            *self.x.end(),
+            *self.y.start(),
+            *self.y.end(),
+        );
+        let bends = [
+            rect.u.0,
+            rect.u.1,
+            2 * x0 - rect.v.0,
+            rect.v.0 + 2 * y1,
+            x0 + y1,
+            2 * x1 - rect.v.1,
+            rect.v.1 + 2 * y0,
+            x1 + y0,
+        ];
+        bends
+            .iter()
+            .flat_map(|&u| [u - 1, u, u + 1])
+            .filter(|&u| u >= rect.u.0 && u <= rect.u.1)
+            .any(|u| {
+                let (lo, hi) = self.row(rect, u);
+                lo <= hi
+            })
+    }
+
+    /// Points of the region outside every diamond, by cutting the diamonds
+    /// out of the region's bounding square in rotated coordinates. Fragments
+    /// that miss the region are dropped as they are cut, so they don't multiply.
+    pub fn uncovered<'a>(&'a self, diamonds: &[Rect]) -> impl Iterator<Item = Pos> + 'a {
+        let mut rects = vec![self.bounding_rect()];
+        rects.retain(|r| self.overlaps(r));
+        for diamond in diamonds {
+            rects = rects
+                .iter()
+                .flat_map(|r| r.subtract(diamond))
+                .filter(|r| self.overlaps(r))
+                .collect();
+        }
+        rects.into_iter().flat_map(move |rect| {
+            (rect.u.0..=rect.u.1).flat_map(move |u| {
+                let (lo, hi) = self.row(&rect, u);
+                (lo..=hi).step_by(2).map(move |v| unrotate(u, v))
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: Pos, b: Pos) -> i32 {
+        (a.0 - b.0).abs() + (a.1 - b.1).abs()
+    }
+
+    #[test]
+    fn test_matches_scan() {
+        let sensors = [
+            ((3, 4), 3),
+            ((10, 2), 5),
+            ((-2, 12), 6),
+            ((9, 11), 2),
+            ((15, 15), 4),
+        ];
+        let diamonds = sensors
+            .iter()
+            .map(|&(c, r)| Rect::diamond(c, r))
+            .collect::<Vec<_>>();
+        for region in [
+            Region {
+                x: 0..=20,
+                y: 0..=20,
+            },
+            Region {
+                x: -5..=3,
+                y: 7..=9,
+            },
+            Region {
+                x: 4..=4,
+                y: -3..=30,
+            },
+            Region {
+                x: 10..=10,
+                y: 2..=2,
+            },
+        ] {
+            let mut found = region.uncovered(&diamonds).collect::<Vec<_>>();
+            found.sort();
+            let mut expected = region
+                .x
+                .clone()
+                .flat_map(|x| region.y.clone().map(move |y| (x, y)))
+                .filter(|&p| sensors.iter().all(|&(c, r)| distance(p, c) > r))
+                .collect::<Vec<_>>();
+            expected.sort();
+            assert_eq!(found, expected, "{:?}", region);
+        }
+    }
+}
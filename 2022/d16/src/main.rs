@@ -1,8 +1,7 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     fmt::Display,
     io::{self, BufRead},
-    iter::once,
     str::FromStr,
 };
 
@@ -32,7 +31,7 @@ impl FromStr for Valve {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let words = s
-            .split(|c| c == ' ' || c == '=' || c == ',' || c == ';')
+            .split([' ', '=', ',', ';'])
             .filter(|w| !w.is_empty())
             .collect::<Vec<_>>();
         assert_eq!(words[0], "Valve");
@@ -66,7 +65,7 @@ impl Display for Valve {
             "{}: flow_rate {}, tunnels: {}",
             valve_name(&self.id),
             self.flow_rate,
-            self.tunnels.iter().map(|id| valve_name(&id)).join(",")
+            self.tunnels.iter().map(valve_name).join(",")
         )
     }
 }
@@ -81,7 +80,7 @@ fn read_input() -> Valves {
     io::stdin()
         .lock()
         .lines()
-        .filter_map(Result::ok)
+        .map_while(Result::ok)
         .filter_map(|ln| Valve::from_str(&ln).ok())
         .map(|valve| (valve.id, valve))
         .collect()
@@ -97,8 +96,8 @@ fn total_pressure_released(valves: &Valves, mut time: usize, mut path: &[Move])
     let mut total = 0;
     let mut pos = valve_id("AA");
     let mut flow = 0;
-    path = &path[0..path.len().min(time as usize)];
-    while path.len() > 0 {
+    path = &path[0..path.len().min(time)];
+    while !path.is_empty() {
         total += flow;
         time -= 1;
         if let Move::Go(next, _) = path[0] {
@@ -114,356 +113,296 @@ fn total_pressure_released(valves: &Valves, mut time: usize, mut path: &[Move])
     total
 }
 
-fn find_path(valves: &Valves, from: &ValveID, to: &ValveID) -> Vec<ValveID> {
-    let mut to_visit: Vec<ValveID> = vec![*from];
-    let mut prev: HashMap<ValveID, ValveID> = HashMap::new();
-    while !to_visit.is_empty() {
-        let current = valves.get(&to_visit.remove(0)).unwrap();
-        for neighbour in current.tunnels.iter() {
-            if neighbour.eq(to) {
-                let mut path = vec![current.id, *to];
-                let mut pos = current.id;
-                while path[0].ne(from) {
-                    pos = *prev.get(&pos).unwrap();
-                    path.insert(0, pos);
-                }
-                return path;
-            }
-            if !prev.contains_key(neighbour) {
-                prev.insert(*neighbour, current.id);
-            }
-            if !to_visit.contains(neighbour) {
-                to_visit.push(*neighbour);
-            }
-        }
-    }
-
-    vec![]
-}
+const UNREACHABLE: usize = usize::MAX / 2;
 
-type PathCache = HashMap<(ValveID, ValveID), Vec<ValveID>>;
-
-fn find_cached_path<'a>(paths: &'a PathCache, from: &ValveID, to: &ValveID) -> &'a [ValveID] {
-    paths.get(&(*from, *to)).unwrap_or_else(|| {
-        panic!(
-            "no path between {} and {}",
-            valve_name(from),
-            valve_name(to)
-        )
-    })
+/// Valves with positive flow, plus the start, with shortest distances between all valves.
+struct Network {
+    ids: Vec<ValveID>,
+    flow: Vec<usize>,
+    dist: Vec<Vec<usize>>,
+    next_hop: Vec<Vec<usize>>,
+    useful: Vec<usize>,
+    start: usize,
 }
 
-fn cache_paths(valves: &Valves, between: &Vec<(ValveID, ValveID)>) -> PathCache {
-    let mut cache = HashMap::new();
-    for (from, to) in between {
-        cache.insert((*from, *to), find_path(valves, from, to));
-    }
-    cache
-}
-
-fn find_best_part(
-    valves: &Valves,
-    paths: &PathCache,
-    from: &ValveID,
-    time: usize,
-    flow: usize,
-    acc_flow: usize,
-    left_to_open: Vec<ValveID>,
-) -> usize {
-    if time == 0 {
-        return acc_flow;
-    } else if left_to_open.is_empty() {
-        return acc_flow + flow * time;
-    }
-
-    let mut max = acc_flow + flow * time;
-    for next in left_to_open.iter() {
-        // is path feasible?
-        let path = find_cached_path(paths, from, &next);
-        if path.len() > time {
-            continue;
+impl Network {
+    fn new(valves: &Valves, start: &ValveID) -> Network {
+        let ids = valves.keys().copied().sorted().collect::<Vec<_>>();
+        let index: HashMap<ValveID, usize> =
+            ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let n = ids.len();
+
+        // Floyd–Warshall
+        let mut dist = vec![vec![UNREACHABLE; n]; n];
+        let mut next_hop = vec![vec![0; n]; n];
+        for (i, id) in ids.iter().enumerate() {
+            dist[i][i] = 0;
+            next_hop[i][i] = i;
+            for to in valves.get(id).unwrap().tunnels.iter() {
+                let j = *index.get(to).unwrap();
+                dist[i][j] = 1;
+                next_hop[i][j] = j;
+            }
+        }
+        for k in 0..n {
+            for i in 0..n {
+                for j in 0..n {
+                    if dist[i][k] + dist[k][j] < dist[i][j] {
+                        dist[i][j] = dist[i][k] + dist[k][j];
+                        next_hop[i][j] = next_hop[i][k];
+                    }
+                }
+            }
         }
-        // take path
-        let valve_flow = valves.get(next).unwrap().flow_rate;
-        let next_left_to_open = left_to_open
+
+        let flow = ids
             .iter()
-            .filter(|&v| v != next)
-            .map(|v| *v)
+            .map(|id| valves.get(id).unwrap().flow_rate)
             .collect::<Vec<_>>();
-        let next_max = find_best_part(
-            valves,
-            paths,
-            &next,
-            time - path.len(),
-            flow + valve_flow,
-            acc_flow + flow * path.len(),
-            next_left_to_open,
+        let useful = (0..n).filter(|&i| flow[i] > 0).collect::<Vec<_>>();
+        assert!(
+            useful.len() < usize::BITS as usize,
+            "too many valves with flow"
         );
-        if next_max > max {
-            max = next_max;
+        Network {
+            start: *index.get(start).unwrap(),
+            ids,
+            flow,
+            dist,
+            next_hop,
+            useful,
         }
     }
-    max
-}
 
-fn part1(valves: &Valves, time: usize) -> usize {
-    let left_to_open = valves
-        .values()
-        .filter(|v| v.flow_rate > 0)
-        .map(|v| v.id)
-        .collect::<Vec<_>>();
-    let path_cache = cache_paths(
-        valves,
-        &valves
-            .keys()
-            .permutations(2)
-            .map(|p| (*p[0], *p[1]))
-            .collect::<Vec<_>>(),
-    );
-    let from = valve_id("AA");
-
-    find_best_part(valves, &path_cache, &from, time, 0, 0, left_to_open)
-}
+    /// Valves visited walking from `from` to `to`, excluding `from`.
+    fn path(&self, mut from: usize, to: usize) -> Vec<usize> {
+        let mut path = vec![];
+        while from != to {
+            from = self.next_hop[from][to];
+            path.push(from);
+        }
+        path
+    }
 
-#[derive(Clone)]
-struct Actor<'a> {
-    pos: ValveID,
-    path: &'a [ValveID],
-    moves: Vec<Move>,
-}
+    /// Best pressure a single actor can release by opening exactly the valves
+    /// in each bitmask of `useful`, with the order to open them in.
+    fn best_per_subset(&self, time: usize) -> Vec<(usize, Vec<usize>)> {
+        let mut best = vec![(0, vec![]); 1 << self.useful.len()];
+        self.explore(self.start, time, 0, 0, &mut vec![], &mut best);
+        best
+    }
 
-impl<'a> Actor<'a> {
-    fn next(&self) -> Actor {
-        if self.path.is_empty() {
-            return self.clone();
+    fn explore(
+        &self,
+        pos: usize,
+        time_left: usize,
+        opened: usize,
+        released: usize,
+        order: &mut Vec<usize>,
+        best: &mut Vec<(usize, Vec<usize>)>,
+    ) {
+        if released > best[opened].0 {
+            best[opened] = (released, order.clone());
         }
-        Actor {
-            pos: self.path[0],
-            path: &self.path[1..],
-            moves: self
-                .moves
-                .iter()
-                .map(|m| *m)
-                .chain(once(Move::Go(self.path[0], *self.path.last().unwrap())))
-                .collect(),
+        for (bit, &valve) in self.useful.iter().enumerate() {
+            // walk there and spend a minute opening it
+            let cost = self.dist[pos][valve] + 1;
+            if opened & (1 << bit) != 0 || cost >= time_left {
+                continue;
+            }
+            let time_left = time_left - cost;
+            order.push(bit);
+            self.explore(
+                valve,
+                time_left,
+                opened | (1 << bit),
+                released + time_left * self.flow[valve],
+                order,
+                best,
+            );
+            order.pop();
         }
     }
 
-    fn destination(&self) -> Option<ValveID> {
-        self.path.last().map(|i| *i)
+    fn moves(&self, order: &[usize]) -> Vec<Move> {
+        let mut moves = vec![];
+        let mut pos = self.start;
+        for &bit in order {
+            let valve = self.useful[bit];
+            for hop in self.path(pos, valve) {
+                moves.push(Move::Go(self.ids[hop], self.ids[valve]));
+            }
+            moves.push(Move::Open(self.ids[valve]));
+            pos = valve;
+        }
+        moves
     }
 }
 
-fn find_best_2<'a>(
-    valves: &Valves,
-    paths: &'a PathCache,
-    actors: [Actor<'a>; 2],
-    step: usize,
-    time: usize,
-    opening: &Vec<ValveID>,
-    flow: usize,
-    acc_flow: usize,
-    left_to_open: &Vec<ValveID>,
-) -> (usize, Vec<Move>, Vec<Move>) {
-    if step == 2 || (step == 1 && actors[1].pos == [0, 0]) {
-        // next step
-        if time == 0 {
-            assert!(opening.is_empty());
-            return (acc_flow, actors[0].moves.to_vec(), actors[1].moves.to_vec());
-        } else if left_to_open.is_empty() {
-            assert!(opening.is_empty());
-            return (
-                acc_flow + flow * time,
-                actors[0].moves.to_vec(),
-                actors[1].moves.to_vec(),
-            );
-        }
-
-        let opening_flow: usize = opening
-            .iter()
-            .map(|v| valves.get(v).unwrap())
-            .map(|v| v.flow_rate)
-            .sum();
+struct Plan {
+    released: usize,
+    moves: Vec<Vec<Move>>,
+}
 
-        return find_best_2(
-            valves,
-            paths,
-            actors,
-            0,
-            time - 1,
-            &vec![],
-            flow + opening_flow,
-            acc_flow + flow,
-            &left_to_open
-                .iter()
-                .filter(|&v| !opening.contains(v))
-                .map(|v| *v)
-                .collect::<Vec<_>>(),
-        );
+/// Finds the most pressure `actors` working together can release in `time` minutes
+/// starting at AA, with each actor opening a disjoint set of valves.
+fn find_best(valves: &Valves, actors: usize, time: usize) -> Plan {
+    assert!(actors > 0);
+    let network = Network::new(valves, &valve_id("AA"));
+    let best = network.best_per_subset(time);
+    let all = best.len() - 1;
+
+    // best[mask] only covers opening exactly mask, allow opening a subset of it
+    let mut best_within = (0..best.len())
+        .map(|mask| (best[mask].0, mask))
+        .collect::<Vec<_>>();
+    for bit in 0..network.useful.len() {
+        for mask in 0..best.len() {
+            if mask & (1 << bit) != 0 && best_within[mask ^ (1 << bit)].0 > best_within[mask].0 {
+                best_within[mask] = best_within[mask ^ (1 << bit)];
+            }
+        }
     }
 
-    let actor = actors[step].clone();
-    if actor.path.is_empty() {
-        // reached end of path
-        if left_to_open.contains(&actor.pos) && !opening.contains(&actor.pos) {
-            // open valve
-            let next_actor = Actor {
-                pos: actor.pos,
-                path: actor.path,
-                moves: actor
-                    .moves
-                    .iter()
-                    .map(|m| *m)
-                    .chain(once(Move::Open(actor.pos)))
-                    .collect_vec(),
-            };
-            return find_best_2(
-                valves,
-                paths,
-                match step {
-                    0 => [next_actor, actors[1].clone()],
-                    1 => [actors[0].clone(), next_actor],
-                    _ => panic!(),
-                },
-                step + 1,
-                time,
-                &opening.iter().chain(once(&actor.pos)).map(|v| *v).collect(),
-                flow,
-                acc_flow,
-                left_to_open,
-            );
-        } else {
-            // already opened - chose new path
-            let mut max = (
-                acc_flow + flow * time,
-                actors[0].moves.to_vec(),
-                actors[1].moves.to_vec(),
-            );
-            let from = &actor.pos;
-            for next in left_to_open.into_iter().filter(|&&v| {
-                v != *from
-                    && Some(v) != actors[0].destination()
-                    && Some(v) != actors[1].destination()
-            }) {
-                // is path feasible?
-                let path = find_cached_path(paths, from, &next);
-                if path.len() > time {
-                    continue;
+    // team[n][mask]: best release by n + 1 actors sharing mask, and the part given to the last actor
+    let mut team = vec![(0..best.len())
+        .map(|mask| (best_within[mask].0, mask))
+        .collect::<Vec<_>>()];
+    for n in 1..actors {
+        let mut next = vec![(0, 0); best.len()];
+        for mask in 0..best.len() {
+            // iterate over all submasks of mask
+            let mut part = mask;
+            loop {
+                let released = best_within[part].0 + team[n - 1][mask ^ part].0;
+                if released > next[mask].0 {
+                    next[mask] = (released, part);
                 }
-                // take path
-                let next_actor = Actor {
-                    pos: path[1],
-                    path: &path[2..],
-                    moves: actor
-                        .moves
-                        .iter()
-                        .map(|m| *m)
-                        .chain(once(Move::Go(path[1], *path.last().unwrap())))
-                        .collect_vec(),
-                };
-                let next_max = find_best_2(
-                    valves,
-                    paths,
-                    match step {
-                        0 => [next_actor, actors[1].clone()],
-                        1 => [actors[0].clone(), next_actor],
-                        _ => panic!(),
-                    },
-                    step + 1,
-                    time,
-                    opening,
-                    flow,
-                    acc_flow,
-                    left_to_open,
-                );
-                if next_max.0 > max.0 {
-                    max = next_max;
+                if part == 0 {
+                    break;
                 }
+                part = (part - 1) & mask;
             }
-            return max;
         }
-    } else {
-        // move to next step
-        return find_best_2(
-            valves,
-            paths,
-            match step {
-                0 => [actor.next(), actors[1].clone()],
-                1 => [actors[0].clone(), actor.next()],
-                _ => panic!(),
-            },
-            step + 1,
-            time,
-            opening,
-            flow,
-            acc_flow,
-            left_to_open,
-        );
+        team.push(next);
+    }
+
+    let mut moves = vec![];
+    let mut mask = all;
+    for n in (0..actors).rev() {
+        let part = team[n][mask].1;
+        moves.push(network.moves(&best[best_within[part].1].1));
+        mask ^= part;
+    }
+    Plan {
+        released: team[actors - 1][all].0,
+        moves,
     }
 }
 
-fn part2(valves: &Valves, time: usize) -> usize {
-    let left_to_open = valves
-        .values()
-        .filter(|v| v.flow_rate > 0)
-        .map(|v| v.id)
-        .collect::<Vec<_>>();
-    let path_cache = cache_paths(
-        valves,
-        &valves
-            .keys()
-            .permutations(2)
-            .map(|p| (*p[0], *p[1]))
-            .collect::<Vec<_>>(),
-    );
-    let from = valve_id("AA");
-
-    let (max, m0, m1) = find_best_2(
-        valves,
-        &path_cache,
-        [
-            Actor {
-                pos: from,
-                path: &[],
-                moves: vec![],
-            },
-            Actor {
-                pos: from,
-                path: &[],
-                moves: vec![],
-            },
-        ],
-        0,
-        time,
-        &vec![],
-        0,
-        0,
-        &left_to_open,
-    );
-
-    print_paths(&[&m1, &m0]);
-    return max;
+fn actor_name(actor: usize) -> String {
+    match actor {
+        0 => String::from("you"),
+        1 => String::from("elephant"),
+        n => format!("elephant {}", n),
+    }
 }
 
-fn print_paths(path: &[&Vec<Move>]) {
-    for i in 0..path[0].len() {
+fn print_paths(paths: &[Vec<Move>], time: usize) {
+    for i in 0..time {
         println!("Minute {}", i + 1);
-        for p in 0..path.len() {
-            let (m, x) = match &path[p][i] {
-                Move::Go(v, dst) => (
+        for (actor, path) in paths.iter().enumerate() {
+            let (m, x) = match path.get(i) {
+                Some(Move::Go(v, dst)) => (
                     "move to",
                     format!("{} for {}", valve_name(v), valve_name(dst)),
                 ),
-                Move::Open(v) => ("open", String::from(valve_name(v))),
+                Some(Move::Open(v)) => ("open", String::from(valve_name(v))),
+                None => ("wait", String::new()),
             };
-            println!("{} {} {}", if p == 0 { "you" } else { "elephant" }, m, x);
+            println!("{} {} {}", actor_name(actor), m, x);
         }
     }
 }
 
+fn solve(valves: &Valves, actors: usize, time: usize) -> usize {
+    let plan = find_best(valves, actors, time);
+    print_paths(&plan.moves, time);
+    let simulated: usize = plan
+        .moves
+        .iter()
+        .map(|moves| total_pressure_released(valves, time, moves))
+        .sum();
+    assert_eq!(simulated, plan.released);
+    plan.released
+}
+
 fn main() {
     let valves = read_input();
+    let args = std::env::args()
+        .skip(1)
+        .map(|arg| arg.parse::<usize>().unwrap())
+        .collect::<Vec<_>>();
+
+    if let [actors, time] = args[..] {
+        println!(
+            "{} actors in {} minutes: {}",
+            actors,
+            time,
+            solve(&valves, actors, time)
+        );
+        return;
+    }
+    println!("Part1: {}", solve(&valves, 1, 30));
+    println!("Part2: {}", solve(&valves, 2, 26));
+}
 
-    println!("Part1: {}", part1(&valves, 30));
-    println!("Part2: {}", part2(&valves, 26));
+#[cfg(test)]
+mod tests {
+    use crate::{find_best, total_pressure_released, Valve, Valves};
+
+    const EXAMPLE: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II";
+
+    fn example() -> Valves {
+        EXAMPLE
+            .lines()
+            .map(|ln| ln.parse::<Valve>().unwrap())
+            .map(|valve| (valve.id, valve))
+            .collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let valves = example();
+        for (actors, time, released) in [(1, 30, 1651), (2, 26, 1707)] {
+            let plan = find_best(&valves, actors, time);
+            assert_eq!(plan.released, released);
+            assert_eq!(plan.moves.len(), actors);
+            let simulated: usize = plan
+                .moves
+                .iter()
+                .map(|moves| total_pressure_released(&valves, time, moves))
+                .sum();
+            assert_eq!(simulated, released);
+        }
+    }
+
+    #[test]
+    fn test_more_actors_never_worse() {
+        let valves = example();
+        let released = (1..=4)
+            .map(|actors| find_best(&valves, actors, 20).released)
+            .collect::<Vec<_>>();
+        assert!(released.windows(2).all(|w| w[0] <= w[1]), "{:?}", released);
+        assert_eq!(find_best(&valves, 2, 0).released, 0);
+    }
 }
@@ -0,0 +1,212 @@
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    io::{self, Write},
+};
+
+use crate::{Cube, Pos, PosValue};
+
+/// Corners of each face of a unit cube, in the order of `Pos::neighbours`,
+/// counter-clockwise seen from outside.
+const FACES: [[(i8, i8, i8); 4]; 6] = [
+    [(0, 1, 0), (0, 1, 1), (1, 1, 1), (1, 1, 0)], // up
+    [(0, 0, 0), (1, 0, 0), (1, 0, 1), (0, 0, 1)], // down
+    [(0, 0, 0), (0, 0, 1), (0, 1, 1), (0, 1, 0)], // left
+    [(1, 0, 0), (1, 1, 0), (1, 1, 1), (1, 0, 1)], // right
+    [(0, 0, 1), (1, 0, 1), (1, 1, 1), (0, 1, 1)], // front
+    [(0, 0, 0), (0, 1, 0), (1, 1, 0), (1, 0, 0)], // back
+];
+
+#[derive(Debug)]
+pub struct Component<T: PosValue> {
+    pub cubes: HashSet<Pos<T>>,
+    /// Faces touching the air around the whole droplet, so a component
+    /// inside another one's pocket has none.
+    pub exterior_surface: usize,
+}
+
+#[derive(Debug)]
+pub struct Analysis<T: PosValue> {
+    pub components: Vec<Component<T>>,
+    /// Enclosed air, each sorted by position.
+    pub pockets: Vec<Vec<Pos<T>>>,
+}
+
+impl<T: PosValue> Pos<T> {
+    fn offset(&self, (dx, dy, dz): (i8, i8, i8)) -> Pos<T> {
+        Pos {
+            x: self.x + dx.into(),
+            y: self.y + dy.into(),
+            z: self.z + dz.into(),
+        }
+    }
+}
+
+// splits cells into groups connected by faces
+fn connected<T: PosValue>(cells: &HashSet<Pos<T>>) -> Vec<HashSet<Pos<T>>> {
+    let mut left = cells.clone();
+    let mut groups = vec![];
+    while let Some(&start) = left.iter().next() {
+        left.remove(&start);
+        let mut group = HashSet::from([start]);
+        let mut q = vec![start];
+        while let Some(next) = q.pop() {
+            for nb in next.neighbours() {
+                if left.remove(&nb) {
+                    group.insert(nb);
+                    q.push(nb);
+                }
+            }
+        }
+        groups.push(group);
+    }
+    groups
+}
+
+// air reachable from outside, within the cube around the shape
+fn outside<T: PosValue>(shape: &HashSet<Pos<T>>, cube: &Cube<T>) -> HashSet<Pos<T>> {
+    let mut air = HashSet::from([cube.min]);
+    let mut q = vec![cube.min];
+    while let Some(next) = q.pop() {
+        for nb in next.neighbours() {
+            if cube.contains(&nb) && !shape.contains(&nb) && air.insert(nb) {
+                q.push(nb);
+            }
+        }
+    }
+    air
+}
+
+pub fn analyse<T: PosValue>(shape: &HashSet<Pos<T>>) -> Analysis<T> {
+    let cube = Cube::containing(shape);
+    let air = outside(shape, &cube);
+    let mut components = connected(shape)
+        .into_iter()
+        .map(|cubes| {
+            let exterior_surface = cubes
+                .iter()
+                .flat_map(|c| c.neighbours())
+                .filter(|nb| air.contains(nb))
+                .count();
+            Component {
+                cubes,
+                exterior_surface,
+            }
+        })
+        .collect::<Vec<_>>();
+    components.sort_by_key(|c| c.cubes.iter().min().copied());
+
+    // everything else inside the cube is enclosed
+    let mut seeds = shape
+        .iter()
+        .flat_map(|c| c.neighbours())
+        .filter(|nb| !shape.contains(nb) && !air.contains(nb))
+        .collect::<HashSet<_>>();
+    let mut pockets = vec![];
+    while let Some(&start) = seeds.iter().next() {
+        let mut pocket = HashSet::from([start]);
+        let mut q = vec![start];
+        while let Some(next) = q.pop() {
+            for nb in next.neighbours() {
+                if !shape.contains(&nb) && pocket.insert(nb) {
+                    q.push(nb);
+                }
+            }
+        }
+        seeds.retain(|p| !pocket.contains(p));
+        let mut pocket = pocket.into_iter().collect::<Vec<_>>();
+        pocket.sort();
+        pockets.push(pocket);
+    }
+    pockets.sort();
+
+    Analysis {
+        components,
+        pockets,
+    }
+}
+
+/// Writes every face between the shape and air as a Wavefront OBJ mesh.
+pub fn write_obj<T: PosValue + Display>(
+    shape: &HashSet<Pos<T>>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut cubes = shape.iter().collect::<Vec<_>>();
+    cubes.sort();
+    let mut vertices = 0;
+    for cube in cubes {
+        for (nb, corners) in cube.neighbours().iter().zip(FACES) {
+            if shape.contains(nb) {
+                continue;
+            }
+            for corner in corners {
+                let v = cube.offset(corner);
+                writeln!(out, "v {} {} {}", v.x, v.y, v.z)?;
+            }
+            writeln!(
+                out,
+                "f {} {} {} {}",
+                vertices + 1,
+                vertices + 2,
+                vertices + 3,
+                vertices + 4
+            )?;
+            vertices += 4;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{exterior_surface_area, surface_area};
+
+    const EXAMPLE: &str =
+        "2,2,2 1,2,2 3,2,2 2,1,2 2,3,2 2,2,1 2,2,3 2,2,4 2,2,6 1,2,5 3,2,5 2,1,5 2,3,5";
+
+    fn shape(s: &str) -> HashSet<Pos<i16>> {
+        s.split(' ').map(|p| p.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let shape = shape(EXAMPLE);
+        let analysis = analyse(&shape);
+        assert_eq!(analysis.pockets, vec![vec![Pos { x: 2, y: 2, z: 5 }]]);
+        let exterior: usize = analysis.components.iter().map(|c| c.exterior_surface).sum();
+        assert_eq!(exterior, exterior_surface_area(&shape));
+        assert_eq!(exterior, 58);
+    }
+
+    #[test]
+    fn test_nested() {
+        // a hollow 5x5x5 box with a cube floating inside its 3x3x3 hole
+        let mut shape = HashSet::new();
+        for x in 0..5 {
+            for y in 0..5 {
+                for z in 0..5 {
+                    if [x, y, z].iter().any(|&c| c == 0 || c == 4) {
+                        shape.insert(Pos { x, y, z });
+                    }
+                }
+            }
+        }
+        shape.insert(Pos { x: 2, y: 2, z: 2 });
+        let analysis = analyse(&shape);
+        assert_eq!(analysis.components.len(), 2);
+        assert_eq!(analysis.components[0].exterior_surface, 150);
+        assert_eq!(analysis.components[1].exterior_surface, 0);
+        assert_eq!(analysis.pockets.len(), 1);
+        assert_eq!(analysis.pockets[0].len(), 26);
+
+        let mut obj = vec![];
+        write_obj(&shape, &mut obj).unwrap();
+        let faces = String::from_utf8(obj)
+            .unwrap()
+            .lines()
+            .filter(|ln| ln.starts_with("f "))
+            .count();
+        assert_eq!(faces, surface_area(&shape));
+    }
+}
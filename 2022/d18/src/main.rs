@@ -1,5 +1,9 @@
+mod analysis;
+
 use std::{
     collections::HashSet,
+    fmt::Display,
+    fs::File,
     hash::Hash,
     io::{self, BufRead},
     ops::{Add, Sub},
@@ -61,6 +65,12 @@ where
     }
 }
 
+impl<T: Display> Display for Pos<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
 impl<T> Pos<T>
 where
     T: PosValue,
@@ -136,7 +146,7 @@ fn read_input() -> HashSet<Pos<i16>> {
     io::stdin()
         .lock()
         .lines()
-        .filter_map(Result::ok)
+        .map_while(Result::ok)
         .map(|ln| Pos::from_str(&ln).unwrap())
         .collect()
 }
@@ -226,8 +236,60 @@ fn exterior_surface_area<T: PosValue>(shape: &HashSet<Pos<T>>) -> usize {
 }
 
 fn main() {
+    let mut obj = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--obj" => obj = Some(args.next().expect("--obj takes a file name")),
+            _ => panic!("Unknown argument {}", arg),
+        }
+    }
     let input = read_input();
 
     println!("Surface: {}", surface_area(&input));
     println!("Exterior surface: {}", exterior_surface_area(&input));
+
+    let analysis = analysis::analyse(&input);
+    println!("{} components:", analysis.components.len());
+    for (idx, component) in analysis.components.iter().enumerate() {
+        println!(
+            "  {}: {} cubes, exterior surface {}",
+            idx,
+            component.cubes.len(),
+            component.exterior_surface
+        );
+    }
+    println!("{} air pockets:", analysis.pockets.len());
+    for (idx, pocket) in analysis.pockets.iter().enumerate() {
+        let lo = |f: fn(&Pos<i16>) -> i16| pocket.iter().map(f).min().unwrap();
+        let hi = |f: fn(&Pos<i16>) -> i16| pocket.iter().map(f).max().unwrap();
+        println!(
+            "  {}: volume {} within {},{},{} - {},{},{}",
+            idx,
+            pocket.len(),
+            lo(|p| p.x),
+            lo(|p| p.y),
+            lo(|p| p.z),
+            hi(|p| p.x),
+            hi(|p| p.y),
+            hi(|p| p.z)
+        );
+        if pocket.len() <= 4 {
+            for pos in pocket {
+                println!("    {}", pos);
+            }
+        }
+    }
+
+    if let Some(path) = obj {
+        let written =
+            File::create(&path).and_then(|mut file| analysis::write_obj(&input, &mut file));
+        match written {
+            Ok(()) => println!("Wrote {}", path),
+            Err(err) => {
+                eprintln!("{}: {}", path, err);
+                std::process::exit(1);
+            }
+        }
+    }
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    fmt::Display,
     io::{self, BufRead},
     ops::{Add, Mul, Sub, SubAssign},
     str::FromStr,
@@ -33,7 +33,6 @@ struct Blueprint {
     clay_robot_cost: Amount,
     obsidian_robot_cost: Amount,
     geode_robot_cost: Amount,
-    max_geodes: HashMap<(usize, Amount, Amount), usize>, // (time, robots, materials) => max geodes
 }
 
 impl FromStr for Blueprint {
@@ -71,7 +70,6 @@ impl FromStr for Blueprint {
                 obsidian: cap[7].parse().unwrap(),
                 geode: 0,
             },
-            max_geodes: HashMap::new(),
         })
     }
 }
@@ -80,7 +78,7 @@ fn read_input() -> Vec<Blueprint> {
     io::stdin()
         .lock()
         .lines()
-        .filter_map(Result::ok)
+        .map_while(Result::ok)
         .map(|ln| Blueprint::from_str(&ln).unwrap())
         .collect()
 }
@@ -223,99 +221,317 @@ fn needs_more_miners(
     materials.get(material) + (robots.get(material) * time) < time * blueprint.max_cost(material)
 }
 
-const PRIORITISE_GEODE_MINERS: bool = true;
+impl Material {
+    // most valuable first, so good schedules are found early and prune more
+    const ALL: [Material; 4] = [
+        Material::Geode,
+        Material::Obsidian,
+        Material::Clay,
+        Material::Ore,
+    ];
+}
+
+impl Display for Material {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Material::Ore => "ore",
+            Material::Clay => "clay",
+            Material::Obsidian => "obsidian",
+            Material::Geode => "geode",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ore, {} clay, {} obsidian, {} geode",
+            self.ore, self.clay, self.obsidian, self.geode
+        )
+    }
+}
+
+impl Blueprint {
+    fn robot_cost(&self, robot: Material) -> Amount {
+        match robot {
+            Material::Ore => self.ore_robot_cost,
+            Material::Clay => self.clay_robot_cost,
+            Material::Obsidian => self.obsidian_robot_cost,
+            Material::Geode => self.geode_robot_cost,
+        }
+    }
+}
+
+fn one_robot(robot: Material) -> Amount {
+    match robot {
+        Material::Ore => Amount::ore(1),
+        Material::Clay => Amount::clay(1),
+        Material::Obsidian => Amount::obsidian(1),
+        Material::Geode => Amount::geodes(1),
+    }
+}
 
-fn max_geodes(blueprint: &mut Blueprint, time: usize, robots: Amount, materials: Amount) -> usize {
-    if time == 0 {
-        return materials.geode;
+// minutes to wait until the robots have collected enough for cost
+fn wait_for(cost: &Amount, robots: Amount, materials: Amount) -> Option<usize> {
+    [
+        Material::Ore,
+        Material::Clay,
+        Material::Obsidian,
+        Material::Geode,
+    ]
+    .iter()
+    .map(|&m| {
+        let missing = m.get(cost).saturating_sub(m.get(&materials));
+        match (missing, m.get(&robots)) {
+            (0, _) => Some(0),
+            (_, 0) => None,
+            (missing, rate) => Some(missing.div_ceil(rate)),
+        }
+    })
+    .try_fold(0, |wait, w| w.map(|w| wait.max(w)))
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct SearchStats {
+    visited: usize,
+    pruned_by_bound: usize,
+    pruned_enough_robots: usize,
+    pruned_too_late: usize,
+}
+
+impl Add for SearchStats {
+    type Output = SearchStats;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        SearchStats {
+            visited: self.visited + rhs.visited,
+            pruned_by_bound: self.pruned_by_bound + rhs.pruned_by_bound,
+            pruned_enough_robots: self.pruned_enough_robots + rhs.pruned_enough_robots,
+            pruned_too_late: self.pruned_too_late + rhs.pruned_too_late,
+        }
     }
-    let cache_key = (time, robots, materials);
-    if let Some(cached_max) = blueprint.max_geodes.get(&cache_key) {
-        return *cached_max;
+}
+
+impl Display for SearchStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} states visited, pruned {} by geode bound, {} with enough robots, {} too late to pay off",
+            self.visited, self.pruned_by_bound, self.pruned_enough_robots, self.pruned_too_late
+        )
     }
+}
+
+struct Search<'a> {
+    blueprint: &'a Blueprint,
+    time: usize,
+    best: usize,
+    best_builds: Vec<(usize, Material)>,
+    builds: Vec<(usize, Material)>, // (minute, robot)
+    stats: SearchStats,
+}
 
-    // without building new robot this turn
-    let next_materials = materials.add(robots);
-    let mut max = if materials.geode > 0 {
-        // without building robots at all?
-        // may be wrong optimization
-        materials.geode + (robots.geode * time)
-    } else {
-        max_geodes(blueprint, time - 1, robots, next_materials)
-    };
-
-    // don't care, build a geode miner
-    if materials.can_afford(&blueprint.geode_robot_cost) {
-        max = max.max(max_geodes(
+impl<'a> Search<'a> {
+    fn new(blueprint: &'a Blueprint, time: usize) -> Self {
+        Search {
             blueprint,
-            time - 1,
-            robots.add(Amount::geodes(1)),
-            next_materials.sub(blueprint.geode_robot_cost),
-        ));
-        if PRIORITISE_GEODE_MINERS {
-            blueprint.max_geodes.insert(cache_key, max);
-            return max;
+            time,
+            best: 0,
+            best_builds: vec![],
+            builds: vec![],
+            stats: SearchStats::default(),
         }
     }
 
-    // if any robot can be built, check if it's better to build it
-    // if production >= any robot, only build geode robots
-    let should_build_non_geode = !has_enough_for_robots(blueprint, robots);
-    if should_build_non_geode {
-        if materials.can_afford(&blueprint.ore_robot_cost)
-            && needs_more_miners(blueprint, time, robots, materials, Material::Ore)
-        {
-            max = max.max(max_geodes(
-                blueprint,
-                time - 1,
-                robots.add(Amount::ore(1)),
-                next_materials.sub(blueprint.ore_robot_cost),
-            ));
+    // picks the next robot to build, skipping ahead to the minute it can be afforded
+    fn search(&mut self, time_left: usize, robots: Amount, materials: Amount) {
+        self.stats.visited += 1;
+        let idle_geodes = materials.geode + robots.geode * time_left;
+        if idle_geodes > self.best || self.best_builds.is_empty() {
+            self.best = idle_geodes;
+            self.best_builds = self.builds.clone();
         }
-        if materials.can_afford(&blueprint.clay_robot_cost)
-            && needs_more_miners(blueprint, time, robots, materials, Material::Clay)
-        {
-            max = max.max(max_geodes(
-                blueprint,
-                time - 1,
-                robots.add(Amount::clay(1)),
-                next_materials.sub(blueprint.clay_robot_cost),
-            ));
+        // even building a geode robot every minute can't beat the best
+        if idle_geodes + time_left * time_left.saturating_sub(1) / 2 <= self.best {
+            self.stats.pruned_by_bound += 1;
+            return;
         }
-        if materials.can_afford(&blueprint.obsidian_robot_cost)
-            && needs_more_miners(blueprint, time, robots, materials, Material::Obsidian)
-        {
-            max = max.max(max_geodes(
-                blueprint,
-                time - 1,
-                robots.add(Amount::obsidian(1)),
-                next_materials.sub(blueprint.obsidian_robot_cost),
-            ));
+
+        let only_geodes = has_enough_for_robots(self.blueprint, robots);
+        for robot in Material::ALL {
+            if robot != Material::Geode
+                && (only_geodes
+                    || !needs_more_miners(self.blueprint, time_left, robots, materials, robot))
+            {
+                self.stats.pruned_enough_robots += 1;
+                continue;
+            }
+            let cost = self.blueprint.robot_cost(robot);
+            let wait = match wait_for(&cost, robots, materials) {
+                // a robot built in the last minute never collects anything
+                Some(wait) if wait + 1 < time_left => wait,
+                Some(_) => {
+                    self.stats.pruned_too_late += 1;
+                    continue;
+                }
+                None => continue,
+            };
+            self.builds.push((self.time - time_left + wait + 1, robot));
+            self.search(
+                time_left - wait - 1,
+                robots.add(one_robot(robot)),
+                materials.add(robots.mul(wait + 1)).sub(cost),
+            );
+            self.builds.pop();
         }
     }
+}
 
-    blueprint.max_geodes.insert(cache_key, max);
-    max
+struct Step {
+    minute: usize,
+    built: Option<Material>,
+    robots: Amount,
+    materials: Amount,
+}
+
+struct Evaluation {
+    geodes: usize,
+    schedule: Vec<Step>,
+    stats: SearchStats,
+}
+
+fn schedule(blueprint: &Blueprint, time: usize, builds: &[(usize, Material)]) -> Vec<Step> {
+    let mut robots = Amount::ore(1);
+    let mut materials = Amount::default();
+    let mut steps = vec![];
+    for minute in 1..=time {
+        let built = builds.iter().find(|(m, _)| *m == minute).map(|(_, r)| *r);
+        if let Some(robot) = built {
+            materials -= blueprint.robot_cost(robot);
+        }
+        materials = materials.add(robots);
+        if let Some(robot) = built {
+            robots = robots.add(one_robot(robot));
+        }
+        steps.push(Step {
+            minute,
+            built,
+            robots,
+            materials,
+        });
+    }
+    steps
+}
+
+fn max_geodes(blueprint: &Blueprint, time: usize) -> Evaluation {
+    let mut search = Search::new(blueprint, time);
+    search.search(time, Amount::ore(1), Amount::default());
+    let schedule = schedule(blueprint, time, &search.best_builds);
+    assert_eq!(
+        schedule.last().map_or(0, |s| s.materials.geode),
+        search.best
+    );
+    Evaluation {
+        geodes: search.best,
+        schedule,
+        stats: search.stats,
+    }
 }
 
-fn part1(blueprints: &Vec<Blueprint>) -> usize {
-    blueprints
+fn evaluate(blueprints: &[Blueprint], time: usize, count: usize) -> Vec<(u8, Evaluation)> {
+    blueprints[0..blueprints.len().min(count)]
         .par_iter()
-        .map(|b| {
-            max_geodes(&mut b.clone(), 24, Amount::ore(1), Amount::default()) * (b.id as usize)
-        })
+        .map(|b| (b.id, max_geodes(b, time)))
+        .collect()
+}
+
+fn print_evaluations(evaluations: &[(u8, Evaluation)]) {
+    for (id, evaluation) in evaluations {
+        println!("Blueprint {}: {} geodes", id, evaluation.geodes);
+        for step in evaluation.schedule.iter() {
+            println!(
+                "  minute {:2}: {:14} robots {} | materials {}",
+                step.minute,
+                step.built.map_or(String::new(), |r| format!("build {}", r)),
+                step.robots,
+                step.materials
+            );
+        }
+        println!("  {}", evaluation.stats);
+    }
+    let total = evaluations
+        .iter()
+        .fold(SearchStats::default(), |acc, (_, e)| acc + e.stats);
+    println!("total: {}", total);
+}
+
+fn quality_levels(evaluations: &[(u8, Evaluation)]) -> usize {
+    evaluations
+        .iter()
+        .map(|(id, e)| e.geodes * (*id as usize))
         .sum()
 }
 
-fn part2(blueprints: &Vec<Blueprint>) -> usize {
-    blueprints.as_slice()[0..blueprints.len().min(3)]
-        .par_iter()
-        .map(|b| max_geodes(&mut b.clone(), 32, Amount::ore(1), Amount::default()))
-        .reduce(|| 1, |acc, b| acc * b)
+fn geode_product(evaluations: &[(u8, Evaluation)]) -> usize {
+    evaluations.iter().map(|(_, e)| e.geodes).product()
 }
 
 fn main() {
     let blueprints = read_input();
-    println!("part 1: {}", part1(&blueprints));
-    println!("part 2: {}", part2(&blueprints));
+    let args = std::env::args()
+        .skip(1)
+        .map(|arg| arg.parse::<usize>().unwrap())
+        .collect::<Vec<_>>();
+
+    if let [time, count] = args[..] {
+        let evaluations = evaluate(&blueprints, time, count);
+        print_evaluations(&evaluations);
+        println!("quality levels: {}", quality_levels(&evaluations));
+        println!("geode product: {}", geode_product(&evaluations));
+        return;
+    }
+
+    let evaluations = evaluate(&blueprints, 24, blueprints.len());
+    print_evaluations(&evaluations);
+    println!("part 1: {}", quality_levels(&evaluations));
+
+    let evaluations = evaluate(&blueprints, 32, 3);
+    print_evaluations(&evaluations);
+    println!("part 2: {}", geode_product(&evaluations));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{evaluate, geode_product, quality_levels, Blueprint};
+
+    const EXAMPLE: [&str; 2] = [
+        "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.",
+        "Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.",
+    ];
+
+    #[test]
+    fn test_example() {
+        let blueprints = EXAMPLE
+            .iter()
+            .map(|ln| ln.parse::<Blueprint>().unwrap())
+            .collect::<Vec<_>>();
+
+        let evaluations = evaluate(&blueprints, 24, blueprints.len());
+        let geodes = evaluations
+            .iter()
+            .map(|(_, e)| e.geodes)
+            .collect::<Vec<_>>();
+        assert_eq!(geodes, vec![9, 12]);
+        assert_eq!(quality_levels(&evaluations), 33);
+        for (_, evaluation) in evaluations.iter() {
+            let last = evaluation.schedule.last().unwrap();
+            assert_eq!(last.minute, 24);
+            assert_eq!(last.materials.geode, evaluation.geodes);
+        }
+
+        let evaluations = evaluate(&blueprints, 32, 3);
+        assert_eq!(geode_product(&evaluations), 56 * 62);
+    }
 }
@@ -0,0 +1,267 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+};
+
+use crate::{name_to_string, Name, Value};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ResolveError {
+    Missing(Name),
+    Cycle(Name),
+    /// A number where an operation was needed.
+    NotAnOperation(Name),
+    /// Comparisons only hold or don't, they have no value.
+    Comparison,
+    /// An operation that overflows or divides by zero.
+    Arithmetic(Op, i64, i64),
+}
+
+impl Display for ResolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResolveError::Missing(name) => write!(f, "no monkey named '{}'", name_to_string(name)),
+            ResolveError::Cycle(name) => {
+                write!(f, "monkey '{}' depends on itself", name_to_string(name))
+            }
+            ResolveError::NotAnOperation(name) => {
+                write!(f, "{} is not an operation", name_to_string(name))
+            }
+            ResolveError::Comparison => write!(f, "a comparison has no value"),
+            ResolveError::Arithmetic(op, lhs, rhs) => {
+                write!(f, "{} {} {} can't be computed", lhs, op.symbol(), rhs)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Compare,
+}
+
+impl Op {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Subtract => "-",
+            Op::Multiply => "*",
+            Op::Divide => "/",
+            Op::Compare => "==",
+        }
+    }
+
+    /// `None` on overflow, division by zero, and for comparisons.
+    pub fn apply(&self, lhs: i64, rhs: i64) -> Option<i64> {
+        match self {
+            Op::Add => lhs.checked_add(rhs),
+            Op::Subtract => lhs.checked_sub(rhs),
+            Op::Multiply => lhs.checked_mul(rhs),
+            Op::Divide => lhs.checked_div(rhs),
+            Op::Compare => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(i64),
+    Variable(Name),
+    Operation(Op, Box<Expr>, Box<Expr>),
+}
+
+/// Looks up `name`, failing if it is missing or already being visited.
+pub fn lookup<'a>(
+    name: &Name,
+    values: &'a HashMap<Name, Value>,
+    visiting: &[Name],
+) -> Result<&'a Value, ResolveError> {
+    if visiting.contains(name) {
+        return Err(ResolveError::Cycle(*name));
+    }
+    values.get(name).ok_or(ResolveError::Missing(*name))
+}
+
+impl Expr {
+    /// Builds the expression tree for `name`, keeping `variable` (if any) as an unknown.
+    pub fn build(
+        name: &Name,
+        values: &HashMap<Name, Value>,
+        variable: Option<&Name>,
+    ) -> Result<Expr, ResolveError> {
+        Self::build_visiting(name, values, variable, &mut vec![])
+    }
+
+    fn build_visiting(
+        name: &Name,
+        values: &HashMap<Name, Value>,
+        variable: Option<&Name>,
+        visiting: &mut Vec<Name>,
+    ) -> Result<Expr, ResolveError> {
+        if variable == Some(name) {
+            return Ok(Expr::Variable(*name));
+        }
+        let value = lookup(name, values, visiting)?;
+        let (lhs, rhs) = match value {
+            Value::Number(n) => return Ok(Expr::Number(*n)),
+            _ => value.sides().unwrap(),
+        };
+        visiting.push(*name);
+        let lhs = Self::build_visiting(&lhs, values, variable, visiting)?;
+        let rhs = Self::build_visiting(&rhs, values, variable, visiting)?;
+        visiting.pop();
+        Ok(Expr::Operation(value.op(), Box::new(lhs), Box::new(rhs)))
+    }
+
+    /// Folds every subtree that doesn't depend on a variable into a number.
+    pub fn simplify(self) -> Expr {
+        match self {
+            Expr::Operation(op, lhs, rhs) => {
+                let lhs = lhs.simplify();
+                let rhs = rhs.simplify();
+                if let (Expr::Number(l), Expr::Number(r)) = (&lhs, &rhs) {
+                    if let Some(n) = op.apply(*l, *r) {
+                        return Expr::Number(n);
+                    }
+                }
+                Expr::Operation(op, Box::new(lhs), Box::new(rhs))
+            }
+            expr => expr,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Operation(_, _, _) => write!(f, "({})", self),
+            _ => write!(f, "{}", self),
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        self.write_dot(&mut dot, &mut 0);
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String, next_id: &mut usize) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        match self {
+            Expr::Number(n) => writeln!(dot, "\tn{} [label=\"{}\"]", id, n).unwrap(),
+            Expr::Variable(name) => writeln!(
+                dot,
+                "\tn{} [label=\"{}\", style=filled, fillcolor=gray]",
+                id,
+                name_to_string(name)
+            )
+            .unwrap(),
+            Expr::Operation(op, lhs, rhs) => {
+                writeln!(dot, "\tn{} [shape=circle, label=\"{}\"]", id, op.symbol()).unwrap();
+                let lhs_id = lhs.write_dot(dot, next_id);
+                let rhs_id = rhs.write_dot(dot, next_id);
+                writeln!(dot, "\tn{} -> n{}", id, lhs_id).unwrap();
+                writeln!(dot, "\tn{} -> n{}", id, rhs_id).unwrap();
+            }
+        }
+        id
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{}", n),
+            Expr::Variable(name) => write!(f, "{}", name_to_string(name)),
+            Expr::Operation(op, lhs, rhs) => {
+                lhs.fmt_operand(f)?;
+                write!(f, " {} ", op.symbol())?;
+                rhs.fmt_operand(f)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_value, root_sides};
+
+    fn monkeys(lines: &[&str]) -> HashMap<Name, Value> {
+        lines.iter().map(|ln| parse_value(ln)).collect()
+    }
+
+    fn name(s: &str) -> Name {
+        s.as_bytes().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_cycle() {
+        let input = monkeys(&[
+            "root: aaaa + bbbb",
+            "aaaa: bbbb * cccc",
+            "bbbb: 3",
+            "cccc: aaaa - bbbb",
+        ]);
+        let root = &input[&name("root")];
+        assert_eq!(root.resolve(&input), Err(ResolveError::Cycle(name("aaaa"))));
+        assert_eq!(
+            Expr::build(&name("root"), &input, None),
+            Err(ResolveError::Cycle(name("aaaa")))
+        );
+    }
+
+    #[test]
+    fn test_missing() {
+        let input = monkeys(&["root: aaaa + zzzz", "aaaa: 1"]);
+        let root = &input[&name("root")];
+        assert_eq!(
+            root.resolve(&input),
+            Err(ResolveError::Missing(name("zzzz")))
+        );
+        assert_eq!(
+            Expr::build(&name("root"), &input, Some(&name("humn"))),
+            Err(ResolveError::Missing(name("zzzz")))
+        );
+    }
+
+    #[test]
+    fn test_root_is_number() {
+        let input = monkeys(&["root: 5"]);
+        assert_eq!(
+            root_sides(&input),
+            Err(ResolveError::NotAnOperation(name("root")))
+        );
+        assert_eq!(input[&name("root")].resolve(&input), Ok(5));
+    }
+
+    #[test]
+    fn test_comparison() {
+        let input = monkeys(&["aaaa: 1"]);
+        let compare = Value::Compare(name("aaaa"), name("aaaa"));
+        assert_eq!(compare.resolve(&input), Err(ResolveError::Comparison));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let input = monkeys(&[
+            "root: aaaa / bbbb",
+            "aaaa: 7",
+            "bbbb: cccc - cccc",
+            "cccc: 3",
+        ]);
+        assert_eq!(
+            input[&name("root")].resolve(&input),
+            Err(ResolveError::Arithmetic(Op::Divide, 7, 0))
+        );
+        let input = monkeys(&["root: aaaa * aaaa", "aaaa: 4294967296"]);
+        assert_eq!(
+            input[&name("root")].resolve(&input),
+            Err(ResolveError::Arithmetic(Op::Multiply, 1 << 32, 1 << 32))
+        );
+    }
+}
@@ -1,8 +1,12 @@
+mod expr;
+
 use std::{
     collections::HashMap,
     io::{self, BufRead},
 };
 
+use expr::{lookup, Expr, Op, ResolveError};
+
 use z3::{ast::Ast, ast::Int, Config, Context, Optimize, SatResult};
 
 type Name = [u8; 4];
@@ -67,27 +71,46 @@ fn name_to_string(name: &Name) -> String {
 }
 
 impl Value {
-    fn resolve(&self, values: &HashMap<Name, Value>) -> i64 {
+    fn resolve(&self, values: &HashMap<Name, Value>) -> Result<i64, ResolveError> {
+        self.resolve_visiting(values, &mut vec![])
+    }
+
+    fn resolve_visiting(
+        &self,
+        values: &HashMap<Name, Value>,
+        visiting: &mut Vec<Name>,
+    ) -> Result<i64, ResolveError> {
         if let Value::Number(n) = self {
-            return *n;
+            return Ok(*n);
         }
+        let mut resolve_side = |name: Name| -> Result<i64, ResolveError> {
+            let value = lookup(&name, values, visiting)?;
+            visiting.push(name);
+            let n = value.resolve_visiting(values, visiting)?;
+            visiting.pop();
+            Ok(n)
+        };
         let (lhs, rhs) = match self {
             Value::Add(lhs, rhs)
             | Value::Subtract(lhs, rhs)
             | Value::Multiply(lhs, rhs)
-            | Value::Divide(lhs, rhs) => (
-                values.get(lhs).unwrap().resolve(values),
-                values.get(rhs).unwrap().resolve(values),
-            ),
-            _ => panic!("Unresolvable operation!"),
+            | Value::Divide(lhs, rhs) => (resolve_side(*lhs)?, resolve_side(*rhs)?),
+            _ => return Err(ResolveError::Comparison),
         };
 
+        let op = self.op();
+        op.apply(lhs, rhs)
+            .ok_or(ResolveError::Arithmetic(op, lhs, rhs))
+    }
+
+    fn op(&self) -> Op {
         match self {
-            Value::Add(_, _) => lhs + rhs,
-            Value::Subtract(_, _) => lhs - rhs,
-            Value::Multiply(_, _) => lhs * rhs,
-            Value::Divide(_, _) => lhs / rhs,
-            _ => unreachable!(),
+            Value::Number(_) => panic!("Numbers have no operation!"),
+            Value::Add(_, _) => Op::Add,
+            Value::Subtract(_, _) => Op::Subtract,
+            Value::Multiply(_, _) => Op::Multiply,
+            Value::Divide(_, _) => Op::Divide,
+            Value::Compare(_, _) => Op::Compare,
         }
     }
 
@@ -129,11 +152,53 @@ impl Value {
 }
 
 fn part1(input: &HashMap<Name, Value>) {
-    let root = input.get(&name!(root)).unwrap();
-    println!("root says {}", root.resolve(&input));
+    match lookup(&name!(root), input, &[]).and_then(|root| root.resolve(input)) {
+        Ok(n) => println!("root says {}", n),
+        Err(err) => println!("root can't say anything: {}", err),
+    }
+}
+
+// the two monkeys root compares
+fn root_sides(input: &HashMap<Name, Value>) -> Result<(Name, Name), ResolveError> {
+    let root_name = name!(root);
+    lookup(&root_name, input, &[])?
+        .sides()
+        .ok_or(ResolveError::NotAnOperation(root_name))
+}
+
+fn human_equation(input: &HashMap<Name, Value>) -> Result<Expr, ResolveError> {
+    let humn_name = name!(humn);
+    let (lhs, rhs) = root_sides(input)?;
+    let lhs = Expr::build(&lhs, input, Some(&humn_name))?;
+    let rhs = Expr::build(&rhs, input, Some(&humn_name))?;
+    Ok(Expr::Operation(Op::Compare, Box::new(lhs), Box::new(rhs)).simplify())
+}
+
+fn expression(input: &HashMap<Name, Value>, dot_path: Option<String>) {
+    let equation = match human_equation(input) {
+        Ok(equation) => equation,
+        Err(err) => {
+            println!("can't build equation: {}", err);
+            return;
+        }
+    };
+    println!("equation: {}", equation);
+    if let Some(path) = dot_path {
+        match std::fs::write(&path, equation.to_dot()) {
+            Ok(()) => println!("wrote graph to {}", path),
+            Err(err) => println!("can't write {}: {}", path, err),
+        }
+    }
 }
 
 fn part2(input: &HashMap<Name, Value>) {
+    let root_sides = match root_sides(input) {
+        Ok(sides) => sides,
+        Err(err) => {
+            println!("human can't say anything: {}", err);
+            return;
+        }
+    };
     let cfg = Config::new();
     let ctx = Context::new(&cfg);
     let opt = Optimize::new(&ctx);
@@ -156,7 +221,6 @@ fn part2(input: &HashMap<Name, Value>) {
     }
 
     // add constraints for root
-    let root_sides = input.get(&name!(root)).unwrap().sides().unwrap();
     let cmp_root = Value::Compare(root_sides.0, root_sides.1);
     cmp_root.add_constraints(&root_name, &vars, &ctx, &opt);
 
@@ -170,5 +234,6 @@ fn part2(input: &HashMap<Name, Value>) {
 fn main() {
     let input = read_input();
     part1(&input);
+    expression(&input, std::env::args().nth(1));
     part2(&input);
 }
@@ -1,9 +1,10 @@
 use std::{
     fmt::Display,
     io::{self, Read},
-    str::FromStr,
 };
 
+use args::Args;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tile {
     Empty,
@@ -72,28 +73,31 @@ impl From<char> for Move {
     }
 }
 
-impl FromStr for Board {
-    type Err = ();
+/// How the faces of the cube are laid out, they differ between the example and my input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeLayout {
+    Example,
+    Input,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Board {
+    fn parse(s: &str, layout: CubeLayout) -> Self {
         let mut lines = vec![];
         for line in s.split("\n").filter(|ln| !ln.is_empty()) {
             let start = line.find(|c| c != ' ').unwrap();
             let tiles = line[start..].chars().map(char::into).collect();
             lines.push((start, tiles))
         }
-        let num_lines = lines.len();
-        Ok(Board {
+        Board {
             lines,
-            wrapping: wrapping_map(num_lines),
-        })
+            wrapping: wrapping_map(layout),
+        }
     }
 }
 
-pub fn wrapping_map(lines: usize) -> WrappingMap {
-    let map = if lines == 12 {
-        // sample input
-        [
+pub fn wrapping_map(layout: CubeLayout) -> WrappingMap {
+    let map = match layout {
+        CubeLayout::Example => [
             (Direction::Up, 8, 11, Direction::Down, 3, 0, 0, 4),
             (Direction::Left, 0, 3, Direction::Down, 4, 7, 8, 4),
             (Direction::Left, 4, 7, Direction::Up, 15, 12, 0, 11),
@@ -101,10 +105,8 @@ pub fn wrapping_map(lines: usize) -> WrappingMap {
             (Direction::Down, 4, 7, Direction::Right, 11, 8, 7, 8),
             (Direction::Right, 0, 3, Direction::Left, 11, 8, 11, 15),
             (Direction::Right, 4, 7, Direction::Down, 15, 12, 11, 8),
-        ]
-    } else {
-        // my input
-        [
+        ],
+        CubeLayout::Input => [
             (Direction::Left, 0, 49, Direction::Right, 149, 100, 50, 0),
             (Direction::Left, 50, 99, Direction::Down, 0, 49, 50, 100),
             (Direction::Left, 150, 199, Direction::Down, 50, 99, 0, 0),
@@ -112,7 +114,7 @@ pub fn wrapping_map(lines: usize) -> WrappingMap {
             (Direction::Right, 50, 99, Direction::Up, 100, 149, 99, 49),
             (Direction::Right, 150, 199, Direction::Up, 50, 99, 49, 149),
             (Direction::Down, 0, 49, Direction::Down, 100, 149, 199, 0),
-        ]
+        ],
     };
 
     // calculate mirrored wrapping
@@ -365,11 +367,11 @@ impl Player {
     }
 }
 
-fn read_input() -> (Board, Vec<Move>) {
+fn read_input(layout: CubeLayout) -> (Board, Vec<Move>) {
     let mut buf = String::new();
     let _ = io::stdin().read_to_string(&mut buf);
     let (board_str, moves_str) = buf.split_at(buf.find("\n\n").unwrap());
-    (Board::from_str(board_str).unwrap(), parse_moves(moves_str))
+    (Board::parse(board_str, layout), parse_moves(moves_str))
 }
 
 fn parse_moves(s: &str) -> Vec<Move> {
@@ -392,8 +394,36 @@ fn parse_moves(s: &str) -> Vec<Move> {
     moves
 }
 
+/// How the cube folds, which is all that differs between the example and my input.
+struct Params {
+    layout: CubeLayout,
+}
+
+impl Params {
+    const REAL: Params = Params {
+        layout: CubeLayout::Input,
+    };
+    const EXAMPLE: Params = Params {
+        layout: CubeLayout::Example,
+    };
+
+    fn from_args() -> Params {
+        let mut args = Args::new("d22 [--example]");
+        let params = if args.example() {
+            Params::EXAMPLE
+        } else {
+            Params::REAL
+        };
+        if let Some(flag) = args.next_flag() {
+            args.unknown(&flag);
+        }
+        params
+    }
+}
+
 fn main() {
-    let (board, moves) = read_input();
+    let params = Params::from_args();
+    let (board, moves) = read_input(params.layout);
 
     let mut player = board.starting_position();
     for mv in moves {
@@ -404,11 +434,11 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
-    use crate::{wrap, wrapping_edge_mirror, wrapping_map, Direction, WrappingMap};
+    use crate::{wrap, wrapping_edge_mirror, wrapping_map, CubeLayout, Direction, WrappingMap};
 
     #[test]
     fn test_small_cube_wrapping() {
-        let w = wrapping_map(12);
+        let w = wrapping_map(CubeLayout::Example);
         check_wrapping_map(&w);
         check_wrap(&w, (5, 11, Direction::Right), (8, 14, Direction::Down));
         check_wrap(&w, (11, 10, Direction::Down), (7, 1, Direction::Up));
@@ -0,0 +1,276 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, AddAssign},
+    str::FromStr,
+};
+
+const POSITIVE_BASE_DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const NEGATIVE_BASE_DIGITS: [char; 4] = ['-', '=', '!', '#'];
+
+/// Arbitrary-precision integer in a base with digits `MIN..=MAX`, e.g. SNAFU is `-2..=2`.
+///
+/// Digits `0..=MAX` are written with `POSITIVE_BASE_DIGITS`, negative ones with
+/// `NEGATIVE_BASE_DIGITS`. Letters are lowercase only, so parsing and printing round-trip.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BalancedBase<const MIN: i8, const MAX: i8> {
+    // least significant first, no trailing zeroes
+    digits: Vec<i8>,
+}
+
+pub type Snafu = BalancedBase<-2, 2>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    InvalidDigit(usize, char),
+}
+
+impl<const MIN: i8, const MAX: i8> BalancedBase<MIN, MAX> {
+    const BASE: i8 = {
+        assert!(MIN <= 0 && MAX >= 0, "Base must contain 0");
+        assert!(
+            MIN >= -(NEGATIVE_BASE_DIGITS.len() as i8),
+            "Not enough negative digits"
+        );
+        assert!(
+            MAX < POSITIVE_BASE_DIGITS.len() as i8,
+            "Not enough positive digits"
+        );
+        assert!(MAX - MIN >= 1, "Base must have at least two digits");
+        MAX - MIN + 1
+    };
+
+    fn from_digits(mut digits: Vec<i8>) -> Self {
+        let _ = Self::BASE; // check the digit range for every number type in use
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        BalancedBase { digits }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn signum(&self) -> Ordering {
+        // the most significant digit outweighs all others
+        self.digits.last().map_or(Ordering::Equal, |d| d.cmp(&0))
+    }
+
+    /// The value as an `i128`, or `None` if it doesn't fit.
+    pub fn to_i128(&self) -> Option<i128> {
+        let base = Self::BASE as i128;
+        self.digits.iter().rev().try_fold(0i128, |acc, &d| {
+            let d = d as i128;
+            acc.checked_mul(base)
+                .and_then(|n| n.checked_add(d))
+                // the last digit may bring an overflowing product back in range
+                .or_else(|| {
+                    (acc - acc.signum())
+                        .checked_mul(base)?
+                        .checked_add(d + acc.signum() * base)
+                })
+        })
+    }
+
+    fn digit_to_char(d: i8) -> char {
+        if d >= 0 {
+            POSITIVE_BASE_DIGITS[d as usize] as char
+        } else {
+            NEGATIVE_BASE_DIGITS[(-d - 1) as usize]
+        }
+    }
+
+    fn char_to_digit(c: char) -> Option<i8> {
+        if let Some(pos) = NEGATIVE_BASE_DIGITS.iter().position(|&n| n == c) {
+            let d = -(pos as i8) - 1;
+            return (d >= MIN).then_some(d);
+        }
+        let d = POSITIVE_BASE_DIGITS.iter().position(|&p| p as char == c)? as i8;
+        (d <= MAX).then_some(d)
+    }
+}
+
+impl<const MIN: i8, const MAX: i8> From<i128> for BalancedBase<MIN, MAX> {
+    fn from(mut n: i128) -> Self {
+        let base = Self::BASE as i128;
+        let mut digits = vec![];
+        while n != 0 {
+            let mut quotient = n.div_euclid(base);
+            let mut digit = n.rem_euclid(base);
+            if digit > MAX as i128 {
+                digit -= base;
+                quotient += 1;
+            }
+            digits.push(digit as i8);
+            n = quotient;
+        }
+        Self::from_digits(digits)
+    }
+}
+
+impl<const MIN: i8, const MAX: i8> FromStr for BalancedBase<MIN, MAX> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let digits = s
+            .chars()
+            .enumerate()
+            .map(|(idx, c)| Self::char_to_digit(c).ok_or(ParseError::InvalidDigit(idx, c)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_digits(digits.into_iter().rev().collect()))
+    }
+}
+
+impl<const MIN: i8, const MAX: i8> Display for BalancedBase<MIN, MAX> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let s: String = self
+            .digits
+            .iter()
+            .rev()
+            .map(|&d| Self::digit_to_char(d))
+            .collect();
+        f.pad(&s)
+    }
+}
+
+impl<const MIN: i8, const MAX: i8> Add for &BalancedBase<MIN, MAX> {
+    type Output = BalancedBase<MIN, MAX>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let base = BalancedBase::<MIN, MAX>::BASE;
+        let len = self.digits.len().max(rhs.digits.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry = 0;
+        for i in 0..len {
+            let mut digit =
+                self.digits.get(i).unwrap_or(&0) + rhs.digits.get(i).unwrap_or(&0) + carry;
+            carry = 0;
+            if digit > MAX {
+                digit -= base;
+                carry = 1;
+            } else if digit < MIN {
+                digit += base;
+                carry = -1;
+            }
+            digits.push(digit);
+        }
+        digits.push(carry);
+        BalancedBase::from_digits(digits)
+    }
+}
+
+impl<const MIN: i8, const MAX: i8> Add for BalancedBase<MIN, MAX> {
+    type Output = BalancedBase<MIN, MAX>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+impl<const MIN: i8, const MAX: i8> AddAssign<&BalancedBase<MIN, MAX>> for BalancedBase<MIN, MAX> {
+    fn add_assign(&mut self, rhs: &Self) {
+        *self = &*self + rhs
+    }
+}
+
+impl<const MIN: i8, const MAX: i8> Sum for BalancedBase<MIN, MAX> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |mut acc, n| {
+            acc += &n;
+            acc
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_snafu_examples() {
+        for (n, s) in [
+            (0, "0"),
+            (1, "1"),
+            (3, "1="),
+            (8, "2="),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+            (-3, "-2"),
+        ] {
+            assert_eq!(Snafu::from(n).to_string(), s);
+            assert_eq!(s.parse::<Snafu>().unwrap().to_i128(), Some(n));
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<Snafu>(), Err(ParseError::Empty));
+        assert_eq!(
+            "1=3".parse::<Snafu>(),
+            Err(ParseError::InvalidDigit(2, '3'))
+        );
+        assert_eq!("1!".parse::<Snafu>(), Err(ParseError::InvalidDigit(1, '!')));
+    }
+
+    #[test]
+    fn test_digit_ranges() {
+        // no positive digits other than 0
+        type Negative = BalancedBase<-2, 0>;
+        let n = "=0".parse::<Negative>().unwrap();
+        assert_eq!(n.to_i128(), Some(-6));
+        assert_eq!(n.to_string(), "=0");
+        assert_eq!(
+            "10".parse::<Negative>(),
+            Err(ParseError::InvalidDigit(0, '1'))
+        );
+
+        // letters are lowercase both ways
+        type Base16 = BalancedBase<-1, 14>;
+        assert_eq!(Base16::from(14).to_string(), "e");
+        assert_eq!("e".parse::<Base16>().unwrap().to_i128(), Some(14));
+        assert_eq!("E".parse::<Base16>(), Err(ParseError::InvalidDigit(0, 'E')));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let max = Snafu::from(i128::MAX);
+        assert_eq!(max.to_i128(), Some(i128::MAX));
+        assert_eq!((&max + &Snafu::from(1)).to_i128(), None);
+        assert_eq!(Snafu::from(i128::MIN).to_i128(), Some(i128::MIN));
+    }
+
+    macro_rules! round_trip {
+        ($name: ident, $min: literal, $max: literal) => {
+            proptest! {
+                #[test]
+                fn $name(a: i128, b in i64::MIN..=i64::MAX) {
+                    type N = BalancedBase<$min, $max>;
+                    let encoded = N::from(a);
+                    prop_assert_eq!(encoded.to_i128(), Some(a));
+                    prop_assert_eq!(encoded.to_string().parse::<N>().unwrap(), encoded.clone());
+                    prop_assert_eq!(encoded.signum(), a.cmp(&0));
+
+                    let (a, b) = (a / 2, b as i128);
+                    prop_assert_eq!((&N::from(a) + &N::from(b)).to_i128(), Some(a + b));
+                }
+            }
+        };
+    }
+
+    round_trip!(round_trip_base3, -1, 1);
+    round_trip!(round_trip_base4, -1, 2);
+    round_trip!(round_trip_snafu, -2, 2);
+    round_trip!(round_trip_base9, -4, 4);
+    round_trip!(round_trip_base10, -3, 6);
+}
@@ -0,0 +1,254 @@
+//! Reads capital letters drawn as ASCII art, for puzzles whose answer is a
+//! picture. Knows the 4x6 font and the 6x10 font used over the years.
+
+use std::fmt::Display;
+
+const SMALL_FONT: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[rustfmt::skip]
+const LARGE_FONT: [(char, [&str; 10]); 15] = [
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// A glyph that didn't match any letter, and where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glyph {
+    pub column: usize,
+    pub bitmap: Vec<Vec<bool>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// Lit rows don't fit either font.
+    UnsupportedHeight(usize),
+    /// Some glyphs are not letters, `text` has a `?` in their place.
+    UnknownGlyphs { text: String, glyphs: Vec<Glyph> },
+}
+
+impl Display for Glyph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.bitmap.iter() {
+            let line: String = row.iter().map(|&p| if p { '#' } else { '.' }).collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OcrError::UnsupportedHeight(height) => {
+                write!(f, "no font is {} pixels high", height)
+            }
+            OcrError::UnknownGlyphs { text, glyphs } => {
+                writeln!(f, "read {} with unknown glyphs:", text)?;
+                for glyph in glyphs {
+                    writeln!(f, "at column {}:", glyph.column)?;
+                    write!(f, "{}", glyph)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn parse_glyph(rows: &[&str]) -> Vec<Vec<bool>> {
+    trim_columns(
+        &rows
+            .iter()
+            .map(|row| row.chars().map(|c| c == '#').collect())
+            .collect::<Vec<_>>(),
+    )
+}
+
+// removes empty columns on either side
+fn trim_columns(bitmap: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let width = bitmap.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |x: usize| bitmap.iter().any(|row| row.get(x) == Some(&true));
+    let first = (0..width).find(|&x| lit(x)).unwrap_or(0);
+    let last = (0..width).rev().find(|&x| lit(x)).map_or(0, |x| x + 1);
+    bitmap
+        .iter()
+        .map(|row| {
+            (first..last)
+                .map(|x| *row.get(x).unwrap_or(&false))
+                .collect()
+        })
+        .collect()
+}
+
+fn font(height: usize) -> Option<Vec<(char, Vec<Vec<bool>>)>> {
+    match height {
+        6 => Some(
+            SMALL_FONT
+                .iter()
+                .map(|(c, rows)| (*c, parse_glyph(rows)))
+                .collect(),
+        ),
+        10 => Some(
+            LARGE_FONT
+                .iter()
+                .map(|(c, rows)| (*c, parse_glyph(rows)))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// Reads the letters in `grid`, indexed as `grid[y][x]` with lit pixels `true`.
+///
+/// Letters are told apart by the empty columns between them.
+pub fn recognise(grid: &[Vec<bool>]) -> Result<String, OcrError> {
+    // ignore empty rows around the text
+    let lit_rows = grid
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| row.contains(&true))
+        .map(|(y, _)| y)
+        .collect::<Vec<_>>();
+    let rows = match (lit_rows.first(), lit_rows.last()) {
+        (Some(&first), Some(&last)) => &grid[first..=last],
+        _ => return Ok(String::new()),
+    };
+    let font = font(rows.len()).ok_or(OcrError::UnsupportedHeight(rows.len()))?;
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |x: usize| rows.iter().any(|row| row.get(x) == Some(&true));
+    let mut text = String::new();
+    let mut unknown = vec![];
+    let mut x = 0;
+    while x < width {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < width && lit(x) {
+            x += 1;
+        }
+        let bitmap = trim_columns(
+            &rows
+                .iter()
+                .map(|row| (start..x).map(|x| *row.get(x).unwrap_or(&false)).collect())
+                .collect::<Vec<_>>(),
+        );
+        match font.iter().find(|(_, glyph)| *glyph == bitmap) {
+            Some((c, _)) => text.push(*c),
+            None => {
+                text.push('?');
+                unknown.push(Glyph {
+                    column: start,
+                    bitmap,
+                });
+            }
+        }
+    }
+
+    if unknown.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError::UnknownGlyphs {
+            text,
+            glyphs: unknown,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Vec<Vec<bool>> {
+        rows.iter()
+            .map(|row| row.chars().map(|c| c == '#').collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_small_font() {
+        let text = grid(&[
+            "###..#..#.###....##.###..###..#.....##..",
+            "#..#.#.#..#..#....#.#..#.#..#.#....#..#.",
+            "#..#.##...#..#....#.###..#..#.#....#..#.",
+            "###..#.#..###.....#.#..#.###..#....####.",
+            "#.#..#.#..#....#..#.#..#.#.#..#....#..#.",
+            "#..#.#..#.#.....##..###..#..#.####.#..#.",
+        ]);
+        assert_eq!(recognise(&text), Ok(String::from("RKPJBRLA")));
+    }
+
+    #[test]
+    fn test_large_font() {
+        let mut text = grid(&[
+            "#....#..######",
+            "#....#..#.....",
+            "#....#..#.....",
+            "#....#..#.....",
+            "######..#####.",
+            "#....#..#.....",
+            "#....#..#.....",
+            "#....#..#.....",
+            "#....#..#.....",
+            "#....#..#.....",
+        ]);
+        text.insert(0, vec![false; 14]);
+        assert_eq!(recognise(&text), Ok(String::from("HF")));
+    }
+
+    #[test]
+    fn test_unknown_glyph() {
+        let text = grid(&[
+            "#..#.#...#",
+            "#..#.##.##",
+            "####.#.#.#",
+            "#..#.#...#",
+            "#..#.#...#",
+            "#..#.#...#",
+        ]);
+        let glyph = Glyph {
+            column: 5,
+            bitmap: grid(&["#...#", "##.##", "#.#.#", "#...#", "#...#", "#...#"]),
+        };
+        assert_eq!(
+            recognise(&text),
+            Err(OcrError::UnknownGlyphs {
+                text: String::from("H?"),
+                glyphs: vec![glyph],
+            })
+        );
+    }
+}